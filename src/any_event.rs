@@ -0,0 +1,227 @@
+//! Module containing a type-erased input event.
+
+use crate::{
+    common_inputs::{
+        Char, Key, MouseButton, MousePosition, MouseScroll, PhysicalKey, RawKey, StrSimTuple,
+    },
+    event_log::Event,
+    input_event::*,
+    simulatable::{Simulatable, SimulatableMut, StepError, TrySimulatable, TrySimulatableMut},
+    simulator::{Simulate, TrySimulate},
+};
+
+#[cfg(test)]
+mod test;
+
+/// Any input event over the types in [`common_inputs`](crate::common_inputs).
+///
+/// Unlike tuples, any amount of different events can be stored together
+/// such as in a `Vec<AnyEvent>` and simulated with [`sim_iter`](crate::combinator::Combine::sim_iter).
+/// The simulator must support every event, see [`SimulateAny`].
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, any_event::AnyEvent, event_log::EventLog};
+///
+/// let mut s = EventLog::new();
+/// let events: Vec<AnyEvent> = vec![
+///     Key::Control.down().into(),
+///     Char('c').down().into(),
+///     Char('c').up().into(),
+///     MousePosition.move_to(10, 20).into(),
+///     Key::Control.up().into(),
+/// ];
+/// events.clone().sim_iter().run_with(&mut s);
+///
+/// assert_events!(
+///     s, 0,
+///     Key::Control.down(),
+///     Char('c').down(),
+///     Char('c').up(),
+///     MousePosition.move_to(10, 20),
+///     Key::Control.up(),
+/// );
+///
+/// // logged events can be replayed
+/// let replay: Vec<AnyEvent> = s.events
+///     .into_iter()
+///     .map(|e| AnyEvent::try_from(e).unwrap())
+///     .collect();
+/// assert_eq!(replay, events);
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnyEvent {
+    Key(SetTo<Key, bool>),
+    MouseButton(SetTo<MouseButton, bool>),
+    Char(SetTo<Char, bool>),
+    PhysicalKey(SetTo<PhysicalKey, bool>),
+    RawKey(SetTo<RawKey, bool>),
+    MoveTo(SetTo<MousePosition, (i32, i32)>),
+    MoveBy(ChangeBy<MousePosition, (i32, i32)>),
+    Scroll(ChangeBy<MouseScroll, (i32, i32)>),
+    Text(Execute<String>),
+}
+
+/// A simulator that supports every event of [`AnyEvent`].
+/// This is automatically implemented.
+pub trait SimulateAny:
+    Simulate<SetTo<Key, bool>>
+    + Simulate<SetTo<MouseButton, bool>>
+    + Simulate<SetTo<Char, bool>>
+    + Simulate<SetTo<PhysicalKey, bool>>
+    + Simulate<SetTo<RawKey, bool>>
+    + Simulate<SetTo<MousePosition, (i32, i32)>>
+    + Simulate<ChangeBy<MousePosition, (i32, i32)>>
+    + Simulate<ChangeBy<MouseScroll, (i32, i32)>>
+    + for<'a> Simulate<Execute<StrSimTuple<'a>>>
+{
+}
+
+impl<T> SimulateAny for T where
+    T: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>
+        + Simulate<SetTo<PhysicalKey, bool>>
+        + Simulate<SetTo<RawKey, bool>>
+        + Simulate<SetTo<MousePosition, (i32, i32)>>
+        + Simulate<ChangeBy<MousePosition, (i32, i32)>>
+        + Simulate<ChangeBy<MouseScroll, (i32, i32)>>
+        + for<'a> Simulate<Execute<StrSimTuple<'a>>>
+{
+}
+
+/// A fallible simulator that supports every event of [`AnyEvent`]
+/// with the same error.
+/// This is automatically implemented.
+pub trait TrySimulateAny<E>:
+    TrySimulate<SetTo<Key, bool>, Error = E>
+    + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+    + TrySimulate<SetTo<Char, bool>, Error = E>
+    + TrySimulate<SetTo<PhysicalKey, bool>, Error = E>
+    + TrySimulate<SetTo<RawKey, bool>, Error = E>
+    + TrySimulate<SetTo<MousePosition, (i32, i32)>, Error = E>
+    + TrySimulate<ChangeBy<MousePosition, (i32, i32)>, Error = E>
+    + TrySimulate<ChangeBy<MouseScroll, (i32, i32)>, Error = E>
+    + for<'a> TrySimulate<Execute<StrSimTuple<'a>>, Error = E>
+{
+}
+
+impl<T, E> TrySimulateAny<E> for T where
+    T: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>
+        + TrySimulate<SetTo<PhysicalKey, bool>, Error = E>
+        + TrySimulate<SetTo<RawKey, bool>, Error = E>
+        + TrySimulate<SetTo<MousePosition, (i32, i32)>, Error = E>
+        + TrySimulate<ChangeBy<MousePosition, (i32, i32)>, Error = E>
+        + TrySimulate<ChangeBy<MouseScroll, (i32, i32)>, Error = E>
+        + for<'a> TrySimulate<Execute<StrSimTuple<'a>>, Error = E>
+{
+}
+
+impl<Smltr> Simulatable<Smltr> for AnyEvent
+where
+    Smltr: SimulateAny,
+{
+    fn run_with(self, simulator: &mut Smltr) {
+        match self {
+            AnyEvent::Key(e) => e.run_with(simulator),
+            AnyEvent::MouseButton(e) => e.run_with(simulator),
+            AnyEvent::Char(e) => e.run_with(simulator),
+            AnyEvent::PhysicalKey(e) => e.run_with(simulator),
+            AnyEvent::RawKey(e) => e.run_with(simulator),
+            AnyEvent::MoveTo(e) => e.run_with(simulator),
+            AnyEvent::MoveBy(e) => e.run_with(simulator),
+            AnyEvent::Scroll(e) => e.run_with(simulator),
+            AnyEvent::Text(e) => StrSimTuple(&e.input).execute().run_with(simulator),
+        }
+    }
+}
+
+impl<Smltr, E> TrySimulatable<Smltr, E> for AnyEvent
+where
+    Smltr: TrySimulateAny<E>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        match self {
+            AnyEvent::Key(e) => e.try_run_with(simulator),
+            AnyEvent::MouseButton(e) => e.try_run_with(simulator),
+            AnyEvent::Char(e) => e.try_run_with(simulator),
+            AnyEvent::PhysicalKey(e) => e.try_run_with(simulator),
+            AnyEvent::RawKey(e) => e.try_run_with(simulator),
+            AnyEvent::MoveTo(e) => e.try_run_with(simulator),
+            AnyEvent::MoveBy(e) => e.try_run_with(simulator),
+            AnyEvent::Scroll(e) => e.try_run_with(simulator),
+            AnyEvent::Text(e) => StrSimTuple(&e.input).execute().try_run_with(simulator),
+        }
+    }
+}
+
+impl<Smltr> SimulatableMut<Smltr> for AnyEvent
+where
+    Smltr: SimulateAny,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        self.clone().run_with(simulator)
+    }
+}
+
+impl<Smltr, E> TrySimulatableMut<Smltr, E> for AnyEvent
+where
+    Smltr: TrySimulateAny<E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.clone().try_run_with(simulator)
+    }
+}
+
+macro_rules! impl_conversions {
+    ($( $ty:ty => $variant:ident, )*) => {
+        $(
+            impl From<$ty> for AnyEvent {
+                fn from(value: $ty) -> Self {
+                    AnyEvent::$variant(value)
+                }
+            }
+        )*
+
+        impl From<AnyEvent> for Event {
+            fn from(value: AnyEvent) -> Self {
+                match value {
+                    $(AnyEvent::$variant(e) => Event::$variant(e),)*
+                }
+            }
+        }
+
+        /// Logged events can be replayed, except [`Event::Other`].
+        impl TryFrom<Event> for AnyEvent {
+            type Error = Event;
+
+            fn try_from(value: Event) -> Result<Self, Self::Error> {
+                match value {
+                    $(Event::$variant(e) => Ok(AnyEvent::$variant(e)),)*
+                    other => Err(other),
+                }
+            }
+        }
+    };
+}
+
+impl_conversions! {
+    SetTo<Key, bool> => Key,
+    SetTo<MouseButton, bool> => MouseButton,
+    SetTo<Char, bool> => Char,
+    SetTo<PhysicalKey, bool> => PhysicalKey,
+    SetTo<RawKey, bool> => RawKey,
+    SetTo<MousePosition, (i32, i32)> => MoveTo,
+    ChangeBy<MousePosition, (i32, i32)> => MoveBy,
+    ChangeBy<MouseScroll, (i32, i32)> => Scroll,
+    Execute<String> => Text,
+}
+
+impl<'a> From<Execute<StrSimTuple<'a>>> for AnyEvent {
+    fn from(value: Execute<StrSimTuple<'a>>) -> Self {
+        AnyEvent::Text(Execute::new(value.input.0.to_owned()))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::event_log::EventLog as S;
+use crate::prelude::*;
+
+use super::*;
+
+#[test]
+fn any_event_round_trip_physical_key() {
+    let mut s = S::new();
+    PhysicalKey::ShiftLeft
+        .down()
+        .then(PhysicalKey::KeyA.click())
+        .then(PhysicalKey::ShiftLeft.up())
+        .run_with(&mut s);
+    assert_eq!(
+        s.events[0].button(),
+        Some((PhysicalKey::ShiftLeft.into(), true))
+    );
+
+    let replay: Vec<AnyEvent> = s
+        .events
+        .iter()
+        .cloned()
+        .map(|e| AnyEvent::try_from(e).unwrap())
+        .collect();
+    let mut r = S::new();
+    replay.sim_iter().run_with(&mut r);
+    assert_eq!(r.events, s.events);
+}
+
+#[test]
+fn any_event_round_trip_raw_key() {
+    let mut s = S::new();
+    RawKey::linux_evdev(30).click().run_with(&mut s);
+    assert_eq!(
+        s.events[1].button(),
+        Some((RawKey::linux_evdev(30).into(), false))
+    );
+
+    let replay: Vec<AnyEvent> = s
+        .events
+        .iter()
+        .cloned()
+        .map(|e| AnyEvent::try_from(e).unwrap())
+        .collect();
+    assert_eq!(
+        replay,
+        [
+            RawKey::linux_evdev(30).down().into(),
+            RawKey::linux_evdev(30).up().into()
+        ]
+    );
+}
@@ -0,0 +1,364 @@
+//! Simulator wrapper that releases every held input.
+
+use std::time::Duration;
+
+use crate::{
+    cancel::{CancelError, CancelHandle, Cancelled},
+    clock::{Clock, TryClock},
+    common_inputs::{
+        ButtonLike, Char, Key, MouseButton, MousePosition, MouseScroll, PhysicalKey, RawKey,
+        StrSimTuple,
+    },
+    input_event::*,
+    simulatable::{Simulatable, TrySimulatable},
+    simulator::{Simulate, TrySimulate},
+    state_tracker::track_held,
+};
+
+#[cfg(test)]
+mod test;
+
+/// A simulator wrapper that remembers every button pressed through it
+/// and not released yet.
+/// They are released with [`AutoRelease::release_all`] or when this is dropped,
+/// in reverse press order.
+///
+/// Remembered simulatables:
+/// - `SetTo   <common_inputs::Key          , bool>`
+/// - `SetTo   <common_inputs::MouseButton  , bool>`
+/// - `SetTo   <common_inputs::Char         , bool>`
+/// - `SetTo   <common_inputs::PhysicalKey  , bool>`
+/// - `SetTo   <common_inputs::RawKey       , bool>`
+///
+/// Passed through:
+/// - `SetTo   <common_inputs::MousePosition, (i32, i32)>`
+/// - `ChangeBy<common_inputs::MousePosition, (i32, i32)>`
+/// - `ChangeBy<common_inputs::MouseScroll  , (i32, i32)>`
+/// - `Execute <common_inputs::StrSimTuple>`
+///
+/// The inner simulator must support the `Key`, `MouseButton` and `Char` simulatables
+/// so they can be released, either infallibly with [`AutoRelease::new`]
+/// or fallibly with [`AutoRelease::new_fallible`].
+/// Physical and raw keys are released the way they were pressed,
+/// so only simulators that support them need to.
+///
+/// ```
+/// # use kemuler::event_log::EventLog as Simulator;
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, auto_release::AutoRelease};
+///
+/// let mut s = Simulator::new();
+/// {
+///     let mut s = AutoRelease::new(&mut s);
+///     Key::Control.down()
+///         .then(MouseButton::Left.down())
+///         .run_with(&mut s);
+///     // oops, forgot to release
+/// }
+/// assert_events!(
+///     s, 0,
+///     Key::Control.down(),
+///     MouseButton::Left.down(),
+///     MouseButton::Left.up(),
+///     Key::Control.up(),
+/// );
+/// ```
+pub struct AutoRelease<Smltr> {
+    inner: Smltr,
+    held: Vec<ButtonLike>,
+    release: fn(&mut Smltr, ButtonLike),
+    // set when a physical or raw key is pressed, the only time they are known to be supported
+    release_physical_key: Option<fn(&mut Smltr, PhysicalKey)>,
+    release_raw_key: Option<fn(&mut Smltr, RawKey)>,
+}
+
+impl<Smltr> AutoRelease<Smltr>
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>,
+{
+    pub fn new(inner: Smltr) -> AutoRelease<Smltr> {
+        AutoRelease {
+            inner,
+            held: Vec::new(),
+            release: release::<Smltr>,
+            release_physical_key: None,
+            release_raw_key: None,
+        }
+    }
+}
+
+impl<Smltr, E> AutoRelease<Smltr>
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>,
+{
+    /// For fallible simulators that don't implement [`Simulate`],
+    /// inputs are released on a best-effort basis and failed releases are ignored.
+    pub fn new_fallible(inner: Smltr) -> AutoRelease<Smltr> {
+        AutoRelease {
+            inner,
+            held: Vec::new(),
+            release: try_release::<Smltr, E>,
+            release_physical_key: None,
+            release_raw_key: None,
+        }
+    }
+}
+
+impl<Smltr> AutoRelease<Smltr> {
+    pub fn inner(&self) -> &Smltr {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Smltr {
+        &mut self.inner
+    }
+
+    /// Every held input, in the order they were pressed.
+    pub fn held_inputs(&self) -> &[ButtonLike] {
+        &self.held
+    }
+
+    /// Release every held input in reverse press order.
+    pub fn release_all(&mut self) {
+        while let Some(input) = self.held.pop() {
+            match input {
+                ButtonLike::PhysicalKey(key) => {
+                    if let Some(release) = self.release_physical_key {
+                        release(&mut self.inner, key)
+                    }
+                }
+                ButtonLike::RawKey(key) => {
+                    if let Some(release) = self.release_raw_key {
+                        release(&mut self.inner, key)
+                    }
+                }
+                input => (self.release)(&mut self.inner, input),
+            }
+        }
+    }
+}
+
+impl<Smltr> Drop for AutoRelease<Smltr> {
+    fn drop(&mut self) {
+        self.release_all()
+    }
+}
+
+fn release<Smltr>(simulator: &mut Smltr, input: ButtonLike)
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>,
+{
+    match input {
+        ButtonLike::Key(key) => key.down().invert().run_with(simulator),
+        ButtonLike::MouseButton(button) => button.down().invert().run_with(simulator),
+        ButtonLike::Char(c) => c.down().invert().run_with(simulator),
+        // released with the function set when they were pressed
+        ButtonLike::PhysicalKey(_) | ButtonLike::RawKey(_) => {}
+    }
+}
+
+fn try_release<Smltr, E>(simulator: &mut Smltr, input: ButtonLike)
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>,
+{
+    let _ = match input {
+        ButtonLike::Key(key) => key.down().invert().try_run_with(simulator),
+        ButtonLike::MouseButton(button) => button.down().invert().try_run_with(simulator),
+        ButtonLike::Char(c) => c.down().invert().try_run_with(simulator),
+        ButtonLike::PhysicalKey(_) | ButtonLike::RawKey(_) => Ok(()),
+    };
+}
+
+fn release_key<Smltr, I>(simulator: &mut Smltr, key: I)
+where
+    Smltr: Simulate<SetTo<I, bool>>,
+{
+    simulator.simulate(SetTo::new(key, false))
+}
+
+fn try_release_key<Smltr, I>(simulator: &mut Smltr, key: I)
+where
+    Smltr: TrySimulate<SetTo<I, bool>>,
+{
+    let _ = simulator.try_simulate(SetTo::new(key, false));
+}
+
+macro_rules! impl_auto_release {
+    (
+        remember { $($remember_ty:ty,)* }
+        remember_key { $($key_ty:ty => $release:ident,)* }
+        pass { $($(#[$attr:meta])* $pass_ty:ty,)* }
+    ) => {
+        $(
+            impl_auto_release!(@impl $remember_ty, |this, event| {
+                track_held(&mut this.held, event.input.into(), event.to)
+            });
+        )*
+        $(
+            impl<Smltr> Simulate<SetTo<$key_ty, bool>> for AutoRelease<Smltr>
+            where
+                Smltr: Simulate<SetTo<$key_ty, bool>>,
+            {
+                fn simulate(&mut self, simulatable: SetTo<$key_ty, bool>) {
+                    self.inner.simulate(simulatable);
+                    self.$release = Some(release_key::<Smltr, $key_ty>);
+                    track_held(&mut self.held, simulatable.input.into(), simulatable.to)
+                }
+            }
+
+            impl<Smltr> TrySimulate<SetTo<$key_ty, bool>> for AutoRelease<Smltr>
+            where
+                Smltr: TrySimulate<SetTo<$key_ty, bool>>,
+            {
+                type Error = Smltr::Error;
+
+                fn try_simulate(&mut self, simulatable: SetTo<$key_ty, bool>) -> Result<(), Self::Error> {
+                    self.inner.try_simulate(simulatable)?;
+                    self.$release = Some(try_release_key::<Smltr, $key_ty>);
+                    track_held(&mut self.held, simulatable.input.into(), simulatable.to);
+                    Ok(())
+                }
+            }
+        )*
+        $(
+            impl_auto_release!(@impl $(#[$attr])* $pass_ty, |_this, _event| {});
+        )*
+    };
+    (@impl $(#[$attr:meta])* $ty:ty, |$this:ident, $event:ident| $remember:block) => {
+        $(#[$attr])*
+        impl<Smltr> Simulate<$ty> for AutoRelease<Smltr>
+        where
+            Smltr: Simulate<$ty>,
+        {
+            fn simulate(&mut self, simulatable: $ty) {
+                self.inner.simulate(simulatable);
+                let ($this, $event) = (self, simulatable);
+                $remember
+            }
+        }
+
+        $(#[$attr])*
+        impl<Smltr> TrySimulate<$ty> for AutoRelease<Smltr>
+        where
+            Smltr: TrySimulate<$ty>,
+        {
+            type Error = Smltr::Error;
+
+            fn try_simulate(&mut self, simulatable: $ty) -> Result<(), Self::Error> {
+                self.inner.try_simulate(simulatable)?;
+                let ($this, $event) = (self, simulatable);
+                $remember
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_auto_release! {
+    remember {
+        SetTo<Key, bool>,
+        SetTo<MouseButton, bool>,
+        SetTo<Char, bool>,
+    }
+    remember_key {
+        PhysicalKey => release_physical_key,
+        RawKey => release_raw_key,
+    }
+    pass {
+        SetTo<MousePosition, (i32, i32)>,
+        ChangeBy<MousePosition, (i32, i32)>,
+        ChangeBy<MouseScroll, (i32, i32)>,
+    }
+}
+
+impl<'a, Smltr> Simulate<Execute<StrSimTuple<'a>>> for AutoRelease<Smltr>
+where
+    Smltr: Simulate<Execute<StrSimTuple<'a>>>,
+{
+    fn simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) {
+        self.inner.simulate(simulatable)
+    }
+}
+
+impl<'a, Smltr> TrySimulate<Execute<StrSimTuple<'a>>> for AutoRelease<Smltr>
+where
+    Smltr: TrySimulate<Execute<StrSimTuple<'a>>>,
+{
+    type Error = Smltr::Error;
+
+    fn try_simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) -> Result<(), Self::Error> {
+        self.inner.try_simulate(simulatable)
+    }
+}
+
+impl<Smltr> Clock for AutoRelease<Smltr>
+where
+    Smltr: Clock,
+{
+    fn now(&self) -> Duration {
+        self.inner.now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.inner.sleep(duration)
+    }
+
+    fn spin_sleep(&mut self, duration: Duration) {
+        self.inner.spin_sleep(duration)
+    }
+
+    fn sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        self.inner.sleep_or_cancel(duration, handle)
+    }
+
+    fn spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        self.inner.spin_sleep_or_cancel(duration, handle)
+    }
+}
+
+impl<Smltr> TryClock for AutoRelease<Smltr>
+where
+    Smltr: TryClock,
+{
+    type Error = Smltr::Error;
+
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        self.inner.try_sleep(duration)
+    }
+
+    fn try_spin_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        self.inner.try_spin_sleep(duration)
+    }
+
+    fn try_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        self.inner.try_sleep_or_cancel(duration, handle)
+    }
+
+    fn try_spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        self.inner.try_spin_sleep_or_cancel(duration, handle)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::prelude::*;
+use crate::shortcut::ShortcutInput;
+use crate::simulator::{Simulate, TrySimulate};
+
+use super::*;
+
+/// Only fallibly simulates inputs, logging them and failing on Char('!').
+#[derive(Default)]
+struct Fallible {
+    log: Vec<ButtonLike>,
+}
+
+impl<I: Into<ButtonLike>> TrySimulate<SetTo<I, bool>> for Fallible {
+    type Error = ();
+
+    fn try_simulate(&mut self, simulatable: SetTo<I, bool>) -> Result<(), ()> {
+        let input = simulatable.input.into();
+        if input == Char('!').into() && !simulatable.to {
+            return Err(());
+        }
+        self.log.push(input);
+        Ok(())
+    }
+}
+
+#[test]
+fn auto_release_fallible() {
+    let mut inner = Fallible::default();
+    {
+        let mut s = AutoRelease::new_fallible(&mut inner);
+        Key::Control
+            .down()
+            .then(Char('!').down())
+            .then(MouseButton::Left.down())
+            .try_run_with(&mut s)
+            .unwrap();
+        assert_eq!(s.held_inputs().len(), 3);
+    }
+    // the failed release of Char('!') doesn't stop the others
+    assert_eq!(
+        inner.log,
+        [
+            Key::Control.into(),
+            Char('!').into(),
+            MouseButton::Left.into(),
+            MouseButton::Left.into(),
+            Key::Control.into(),
+        ]
+    );
+}
+
+#[test]
+fn auto_release_physical_key() {
+    let mut inner = Fallible::default();
+    {
+        let mut s = AutoRelease::new_fallible(&mut inner);
+        PhysicalKey::ShiftLeft
+            .down()
+            .then(PhysicalKey::KeyA.down())
+            .try_run_with(&mut s)
+            .unwrap();
+    }
+    assert_eq!(
+        inner.log,
+        [
+            PhysicalKey::ShiftLeft.into(),
+            PhysicalKey::KeyA.into(),
+            PhysicalKey::KeyA.into(),
+            PhysicalKey::ShiftLeft.into(),
+        ]
+    );
+}
+
+#[test]
+fn auto_release_raw_key() {
+    let mut inner = Fallible::default();
+    {
+        let mut s = AutoRelease::new_fallible(&mut inner);
+        RawKey::x11_keysym(0xffe1)
+            .down()
+            .try_run_with(&mut s)
+            .unwrap();
+        assert_eq!(s.held_inputs(), [RawKey::x11_keysym(0xffe1).into()]);
+    }
+    assert_eq!(inner.log, [RawKey::x11_keysym(0xffe1).into(); 2]);
+}
+
+/// Only simulates the inputs a shortcut can name, no physical or raw keys.
+#[derive(Default)]
+struct Named {
+    log: Vec<ButtonLike>,
+}
+
+impl<I: Into<ShortcutInput>> Simulate<SetTo<I, bool>> for Named {
+    fn simulate(&mut self, simulatable: SetTo<I, bool>) {
+        self.log.push(simulatable.input.into().into());
+    }
+}
+
+#[test]
+fn auto_release_without_physical_keys() {
+    let mut inner = Named::default();
+    {
+        let mut s = AutoRelease::new(&mut inner);
+        Key::Control.down().then(Char('a').down()).run_with(&mut s);
+    }
+    assert_eq!(
+        inner.log,
+        [
+            Key::Control.into(),
+            Char('a').into(),
+            Char('a').into(),
+            Key::Control.into(),
+        ]
+    );
+}
@@ -0,0 +1,405 @@
+//! Stop a running simulation from another thread.
+
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    clock::{Clock, TryClock},
+    common_inputs::{
+        Char, Key, MouseButton, MousePosition, MouseScroll, PhysicalKey, RawKey, StrSimTuple,
+    },
+    input_event::*,
+    simulator::{Simulate, TrySimulate},
+};
+
+#[cfg(test)]
+mod test;
+
+/// A shareable handle to cancel simulations.
+/// Clones share the same cancellation.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    cancelled: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancelHandle {
+    pub fn new() -> CancelHandle {
+        CancelHandle::default()
+    }
+
+    /// Cancel every simulation using this handle.
+    /// Sleeping simulations wake up immediately.
+    pub fn cancel(&self) {
+        let (_, condvar) = &*self.cancelled;
+        *self.lock() = true;
+        condvar.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.lock()
+    }
+
+    /// Fail with [`Cancelled`] if cancelled.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sleep for the duration or until cancelled.
+    /// A duration too long to reach is waited on until cancelled.
+    pub fn sleep(&self, duration: Duration) -> Result<(), Cancelled> {
+        let (_, condvar) = &*self.cancelled;
+        let deadline = Instant::now().checked_add(duration);
+        let mut cancelled = self.lock();
+        while !*cancelled {
+            cancelled = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Ok(());
+                    }
+                    condvar
+                        .wait_timeout(cancelled, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => condvar
+                    .wait(cancelled)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+        Err(Cancelled)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, bool> {
+        let (cancelled, _) = &*self.cancelled;
+        cancelled.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The simulation was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "simulation was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Error of a [`Cancellable`] wrapping a fallible simulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CancelError<E> {
+    /// The simulation was cancelled.
+    Cancelled,
+    /// The inner simulator failed.
+    Inner(E),
+}
+
+impl<E> From<Cancelled> for CancelError<E> {
+    fn from(_: Cancelled) -> Self {
+        CancelError::Cancelled
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for CancelError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelError::Cancelled => Cancelled.fmt(f),
+            CancelError::Inner(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for CancelError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CancelError::Cancelled => None,
+            CancelError::Inner(e) => Some(e),
+        }
+    }
+}
+
+/// A fallible simulator inside a [`Cancellable`],
+/// made with [`Cancellable::new_fallible`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fallible<Smltr>(pub Smltr);
+
+/// A simulator wrapper that can be cancelled with a [`CancelHandle`].
+///
+/// The handle is checked before every input is passed to the inner simulator.
+/// Once cancelled, inputs fail with [`Cancelled`] so
+/// [`try_run_with`](crate::simulatable::TrySimulatable::try_run_with) returns early.
+/// `Sleep` and `SpinSleep` wait with the inner simulator's [`Clock`]
+/// and wake up as soon as the handle is cancelled,
+/// failing with [`Cancelled`] too when fallibly simulated.
+///
+/// Releasing a button is always passed through, even when cancelled,
+/// so combinators like `During` can still release what they held.
+/// Wrap the inner simulator with
+/// [`AutoRelease`](crate::auto_release::AutoRelease)
+/// to also release buttons pressed outside of them.
+///
+/// The inner simulator is infallible by default,
+/// use [`Cancellable::new_fallible`] to wrap one that implements `TrySimulate`.
+///
+/// Supported simulatables:
+/// - `SetTo   <common_inputs::Key          , bool>`
+/// - `SetTo   <common_inputs::MouseButton  , bool>`
+/// - `SetTo   <common_inputs::Char         , bool>`
+/// - `SetTo   <common_inputs::PhysicalKey  , bool>`
+/// - `SetTo   <common_inputs::RawKey       , bool>`
+/// - `SetTo   <common_inputs::MousePosition, (i32, i32)>`
+/// - `ChangeBy<common_inputs::MousePosition, (i32, i32)>`
+/// - `ChangeBy<common_inputs::MouseScroll  , (i32, i32)>`
+/// - `Execute <common_inputs::StrSimTuple>`
+///
+/// ```
+/// # use kemuler::event_log::EventLog as Simulator;
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, cancel::{CancelHandle, Cancellable, Cancelled}};
+/// use kemuler::utils::time::{millis, secs};
+/// use std::thread;
+///
+/// let handle = CancelHandle::new();
+/// let canceller = handle.clone();
+/// thread::spawn(move || {
+///     thread::sleep(millis(50));
+///     canceller.cancel();
+/// });
+///
+/// let mut log = Simulator::new();
+/// let mut s = Cancellable::new(&mut log, handle);
+/// let result = Key::Space.click()
+///     .sleep(secs(1))
+///     .repeat(10_000)
+///     .during(Key::Control.down())
+///     .try_run_with(&mut s);
+///
+/// assert_eq!(result.unwrap_err().error, Cancelled);
+/// assert_events!(
+///     log, 0,
+///     Key::Control.down(),
+///     Key::Space.down(),
+///     Key::Space.up(),
+///     Key::Control.up(),
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Cancellable<Smltr> {
+    inner: Smltr,
+    handle: CancelHandle,
+}
+
+impl<Smltr> Cancellable<Smltr> {
+    pub fn new(inner: Smltr, handle: CancelHandle) -> Cancellable<Smltr> {
+        Cancellable { inner, handle }
+    }
+
+    pub fn handle(&self) -> &CancelHandle {
+        &self.handle
+    }
+
+    pub fn inner(&self) -> &Smltr {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Smltr {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> Smltr {
+        self.inner
+    }
+
+    fn check(&self) -> Result<(), Cancelled> {
+        self.handle.check()
+    }
+}
+
+impl<Smltr> Cancellable<Fallible<Smltr>> {
+    /// Wrap a fallible simulator.
+    /// Its errors are returned as [`CancelError::Inner`].
+    ///
+    /// ```
+    /// # use kemuler::event_log::EventLog as Simulator;
+    /// use kemuler::{prelude::*, cancel::{CancelError, CancelHandle, Cancellable}};
+    ///
+    /// let handle = CancelHandle::new();
+    /// let mut s = Cancellable::new_fallible(Simulator::new(), handle.clone());
+    /// assert_eq!(Key::F1.click().try_run_with(&mut s), Ok(()));
+    ///
+    /// handle.cancel();
+    /// let result = Key::F1.click().try_run_with(&mut s);
+    /// assert_eq!(result.unwrap_err().error, CancelError::Cancelled);
+    /// ```
+    pub fn new_fallible(inner: Smltr, handle: CancelHandle) -> Cancellable<Fallible<Smltr>> {
+        Cancellable::new(Fallible(inner), handle)
+    }
+}
+
+macro_rules! impl_cancellable {
+    (
+        button { $($button_ty:ty,)* }
+        other { $($other_ty:ty,)* }
+    ) => {
+        $(
+            // releases are never cancelled
+            impl_cancellable!(@impl $button_ty => |event| event.to);
+        )*
+        $(
+            impl_cancellable!(@impl $other_ty => |_event| true);
+        )*
+    };
+    (@impl $ty:ty => |$event:ident| $checked:expr) => {
+        impl<Smltr> TrySimulate<$ty> for Cancellable<Smltr>
+        where
+            Smltr: Simulate<$ty>,
+        {
+            type Error = Cancelled;
+
+            fn try_simulate(&mut self, simulatable: $ty) -> Result<(), Cancelled> {
+                let $event = &simulatable;
+                if $checked {
+                    self.check()?;
+                }
+                self.inner.simulate(simulatable);
+                Ok(())
+            }
+        }
+
+        impl<Smltr> TrySimulate<$ty> for Cancellable<Fallible<Smltr>>
+        where
+            Smltr: TrySimulate<$ty>,
+        {
+            type Error = CancelError<Smltr::Error>;
+
+            fn try_simulate(&mut self, simulatable: $ty) -> Result<(), Self::Error> {
+                let $event = &simulatable;
+                if $checked {
+                    self.check()?;
+                }
+                self.inner.0.try_simulate(simulatable).map_err(CancelError::Inner)
+            }
+        }
+    };
+}
+
+impl_cancellable! {
+    button {
+        SetTo<Key, bool>,
+        SetTo<MouseButton, bool>,
+        SetTo<Char, bool>,
+        SetTo<PhysicalKey, bool>,
+        SetTo<RawKey, bool>,
+    }
+    other {
+        SetTo<MousePosition, (i32, i32)>,
+        ChangeBy<MousePosition, (i32, i32)>,
+        ChangeBy<MouseScroll, (i32, i32)>,
+    }
+}
+
+impl<'a, Smltr> TrySimulate<Execute<StrSimTuple<'a>>> for Cancellable<Smltr>
+where
+    Smltr: Simulate<Execute<StrSimTuple<'a>>>,
+{
+    type Error = Cancelled;
+
+    fn try_simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) -> Result<(), Cancelled> {
+        self.check()?;
+        self.inner.simulate(simulatable);
+        Ok(())
+    }
+}
+
+impl<'a, Smltr> TrySimulate<Execute<StrSimTuple<'a>>> for Cancellable<Fallible<Smltr>>
+where
+    Smltr: TrySimulate<Execute<StrSimTuple<'a>>>,
+{
+    type Error = CancelError<Smltr::Error>;
+
+    fn try_simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) -> Result<(), Self::Error> {
+        self.check()?;
+        self.inner
+            .0
+            .try_simulate(simulatable)
+            .map_err(CancelError::Inner)
+    }
+}
+
+impl<Smltr> Clock for Cancellable<Smltr>
+where
+    Smltr: Clock,
+{
+    fn now(&self) -> Duration {
+        self.inner.now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        // once cancelled, the simulation stops at the next input
+        let _ = self.inner.sleep_or_cancel(duration, &self.handle);
+    }
+
+    fn spin_sleep(&mut self, duration: Duration) {
+        let _ = self.inner.spin_sleep_or_cancel(duration, &self.handle);
+    }
+}
+
+impl<Smltr> TryClock for Cancellable<Smltr>
+where
+    Smltr: Clock,
+{
+    type Error = Cancelled;
+
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Cancelled> {
+        self.inner.sleep_or_cancel(duration, &self.handle)
+    }
+
+    fn try_spin_sleep(&mut self, duration: Duration) -> Result<(), Cancelled> {
+        self.inner.spin_sleep_or_cancel(duration, &self.handle)
+    }
+}
+
+impl<Smltr> Clock for Cancellable<Fallible<Smltr>>
+where
+    Smltr: Clock,
+{
+    fn now(&self) -> Duration {
+        self.inner.0.now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        let _ = self.inner.0.sleep_or_cancel(duration, &self.handle);
+    }
+
+    fn spin_sleep(&mut self, duration: Duration) {
+        let _ = self.inner.0.spin_sleep_or_cancel(duration, &self.handle);
+    }
+}
+
+impl<Smltr> TryClock for Cancellable<Fallible<Smltr>>
+where
+    Smltr: TryClock,
+{
+    type Error = CancelError<Smltr::Error>;
+
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        self.inner.0.try_sleep_or_cancel(duration, &self.handle)
+    }
+
+    fn try_spin_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        self.inner
+            .0
+            .try_spin_sleep_or_cancel(duration, &self.handle)
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::combinator::Call;
+use crate::prelude::*;
+use crate::simulatable::StepError;
+use crate::simulator::TrySimulate;
+use crate::utils::time::{millis, secs};
+use crate::{assert_events, event_log::EventLog as S};
+
+use super::*;
+
+#[test]
+fn cancel_during_sleep() {
+    let handle = CancelHandle::new();
+    let canceller = handle.clone();
+    thread::spawn(move || {
+        thread::sleep(millis(50));
+        canceller.cancel();
+    });
+
+    let mut log = S::new();
+    let mut s = Cancellable::new(&mut log, handle);
+    let start = Instant::now();
+    let result = Key::F1
+        .click()
+        .sleep(secs(60))
+        .then(Key::F2.click())
+        .try_run_with(&mut s);
+
+    assert!(start.elapsed() < secs(30));
+    assert_eq!(result.unwrap_err().error, Cancelled);
+    assert_events!(log, 0, Key::F1.down(), Key::F1.up(),);
+    assert_eq!(log.events.len(), 2);
+}
+
+#[test]
+fn cancel_between_steps() {
+    let handle = CancelHandle::new();
+    let canceller = handle.clone();
+
+    let mut log = S::new();
+    let mut s = Cancellable::new(&mut log, handle);
+    let result = Key::F1
+        .click()
+        .then(Call::new(move |_: &mut _| {
+            canceller.cancel();
+            Ok(())
+        }))
+        .then(Key::F2.click())
+        .during(Key::Shift.down())
+        .try_run_with(&mut s);
+
+    assert_eq!(result.unwrap_err().error, Cancelled);
+    assert_events!(
+        log,
+        0,
+        Key::Shift.down(),
+        Key::F1.down(),
+        Key::F1.up(),
+        Key::Shift.up(),
+    );
+    assert_eq!(log.events.len(), 4);
+}
+
+#[test]
+fn cancel_sleep_too_long_to_reach() {
+    let handle = CancelHandle::new();
+    handle.cancel();
+    assert_eq!(CancelHandle::sleep(&handle, Duration::MAX), Err(Cancelled));
+}
+
+/// Fails to press any key.
+struct Failing;
+
+impl TrySimulate<SetTo<Key, bool>> for Failing {
+    type Error = &'static str;
+
+    fn try_simulate(&mut self, simulatable: SetTo<Key, bool>) -> Result<(), Self::Error> {
+        if simulatable.to {
+            Err("failed")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn cancel_fallible() {
+    let handle = CancelHandle::new();
+    let mut s = Cancellable::new_fallible(Failing, handle.clone());
+    let result = Key::F1.click().try_run_with(&mut s);
+    assert_eq!(result.unwrap_err().error, CancelError::Inner("failed"));
+
+    handle.cancel();
+    let result = Key::F1.click().try_run_with(&mut s);
+    assert_eq!(result.unwrap_err().error, CancelError::Cancelled);
+    assert_eq!(Key::F1.up().try_run_with(&mut s), Ok(()));
+}
+
+#[test]
+fn cancel_only_sleeps() {
+    let handle = CancelHandle::new();
+    handle.cancel();
+
+    let mut s = Cancellable::new(S::new(), handle.clone());
+    let result = Sleep(secs(60)).repeat(3).try_run_with(&mut s);
+    assert_eq!(result.unwrap_err().error, Cancelled);
+
+    let mut s = Cancellable::new_fallible(S::new(), handle);
+    let result = Sleep(secs(60)).then(Sleep(secs(60))).try_run_with(&mut s);
+    assert_eq!(
+        result.unwrap_err(),
+        StepError::new(CancelError::Cancelled).at(0)
+    );
+}
+
+#[test]
+fn cancel_other_handle() {
+    let other = CancelHandle::new();
+    let mut cancelled = Cancellable::new(S::new(), other.clone());
+    let mut s = Cancellable::new(S::new(), CancelHandle::new());
+    other.cancel();
+
+    let start = Instant::now();
+    assert_eq!(Sleep(millis(50)).try_run_with(&mut s), Ok(()));
+    assert!(start.elapsed() >= millis(50));
+    assert!(Sleep(millis(50)).try_run_with(&mut cancelled).is_err());
+}
@@ -0,0 +1,250 @@
+//! Module for keeping time in a simulator.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::cancel::{CancelError, CancelHandle, Cancelled};
+
+/// A source of time.
+/// Simulators use a clock to simulate `Sleep` and `SpinSleep`,
+/// and implement this trait themselves so combinators can ask them what time it is.
+pub trait Clock {
+    /// Time passed since the clock started.
+    /// This never goes backward.
+    fn now(&self) -> Duration;
+
+    /// Wait for the duration to pass.
+    fn sleep(&mut self, duration: Duration);
+
+    /// Wait accurately for the duration to pass.
+    /// Same as [`Clock::sleep`] by default.
+    fn spin_sleep(&mut self, duration: Duration) {
+        self.sleep(duration)
+    }
+
+    /// Wait for the duration to pass unless the handle is cancelled,
+    /// used by [`Cancellable`](crate::cancel::Cancellable).
+    ///
+    /// By default the handle is checked before and after a whole [`Clock::sleep`],
+    /// which is right for clocks that don't block like [`VirtualClock`].
+    /// Clocks that block should wake up as soon as the handle is cancelled.
+    fn sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        handle.check()?;
+        self.sleep(duration);
+        handle.check()
+    }
+
+    /// Accurate version of [`Clock::sleep_or_cancel`].
+    /// Same as it by default.
+    fn spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        self.sleep_or_cancel(duration, handle)
+    }
+}
+
+impl<C> Clock for &mut C
+where
+    C: Clock + ?Sized,
+{
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+
+    fn spin_sleep(&mut self, duration: Duration) {
+        (**self).spin_sleep(duration)
+    }
+
+    fn sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        (**self).sleep_or_cancel(duration, handle)
+    }
+
+    fn spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        (**self).spin_sleep_or_cancel(duration, handle)
+    }
+}
+
+/// A [`Clock`] of a fallible simulator,
+/// used by `Sleep` and `SpinSleep` when they are fallibly simulated.
+pub trait TryClock: Clock {
+    type Error;
+
+    /// Fallible version of [`Clock::sleep`].
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Self::Error>;
+
+    /// Fallible version of [`Clock::spin_sleep`].
+    /// Same as [`TryClock::try_sleep`] by default.
+    fn try_spin_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        self.try_sleep(duration)
+    }
+
+    /// Fallible version of [`Clock::sleep_or_cancel`],
+    /// used by [`Cancellable::new_fallible`](crate::cancel::Cancellable::new_fallible).
+    ///
+    /// By default the handle is checked before and after a whole [`TryClock::try_sleep`].
+    fn try_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        handle.check()?;
+        self.try_sleep(duration).map_err(CancelError::Inner)?;
+        Ok(handle.check()?)
+    }
+
+    /// Accurate version of [`TryClock::try_sleep_or_cancel`].
+    /// Same as it by default.
+    fn try_spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        self.try_sleep_or_cancel(duration, handle)
+    }
+}
+
+impl<C> TryClock for &mut C
+where
+    C: TryClock + ?Sized,
+{
+    type Error = C::Error;
+
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        (**self).try_sleep(duration)
+    }
+
+    fn try_spin_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        (**self).try_spin_sleep(duration)
+    }
+
+    fn try_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        (**self).try_sleep_or_cancel(duration, handle)
+    }
+
+    fn try_spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        (**self).try_spin_sleep_or_cancel(duration, handle)
+    }
+}
+
+/// The wall clock. Sleeping blocks the thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RealClock {
+    start: Instant,
+}
+
+impl RealClock {
+    /// Start a clock from now.
+    pub fn new() -> RealClock {
+        RealClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        RealClock::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration)
+    }
+
+    #[cfg(feature = "spin_sleep")]
+    fn spin_sleep(&mut self, duration: Duration) {
+        ::spin_sleep::sleep(duration)
+    }
+
+    fn sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        CancelHandle::sleep(handle, duration)
+    }
+
+    #[cfg(feature = "spin_sleep")]
+    fn spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        // wait on the handle for most of the time, then spin for accuracy
+        let start = Instant::now();
+        CancelHandle::sleep(handle, duration.saturating_sub(Duration::from_millis(2)))?;
+        ::spin_sleep::sleep(duration.saturating_sub(start.elapsed()));
+        handle.check()
+    }
+}
+
+/// A clock that only moves when slept or advanced.
+/// Sleeping returns immediately, which makes timing in tests instant and exact.
+///
+/// ```
+/// use kemuler::{prelude::*, clock::{Clock, VirtualClock}, event_log::EventLog};
+/// use kemuler::utils::time::secs;
+///
+/// let mut s = EventLog::with_clock(VirtualClock::new());
+/// Sleep(secs(60)).run_with(&mut s);
+/// assert_eq!(s.now(), secs(60));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VirtualClock {
+    now: Duration,
+}
+
+impl VirtualClock {
+    /// Start a clock at zero.
+    pub fn new() -> VirtualClock {
+        VirtualClock::default()
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Duration {
+        self.now
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.advance(duration)
+    }
+}
@@ -1,16 +1,28 @@
 //! Module of helper combinators
 
 use core::fmt;
-use std::{thread, time::Duration};
+use std::time::Duration;
 
-use crate::{input_event::Invert, simulatable::Simulatable};
+use crate::{
+    clock::{Clock, TryClock},
+    input_event::Invert,
+    simulatable::{
+        DynSimulatable, DynSimulatableMut, Simulatable, SimulatableMut, StepError, TrySimulatable,
+        TrySimulatableMut,
+    },
+    simulator::{Simulate, TrySimulate},
+};
 
 #[cfg(test)]
 mod test;
 
+mod chord;
 #[cfg(feature = "spin_sleep")]
 mod spin_sleep;
 
+pub use self::chord::Chord;
+pub(crate) use self::chord::{run_chord, try_run_chord};
+
 #[cfg(feature = "spin_sleep")]
 pub use self::spin_sleep::SpinSleep;
 
@@ -40,6 +52,45 @@ pub trait Combine: Sized {
         }
     }
 
+    /// Repeat simulation for amount of times, by reference.
+    /// Self doesn't need to be `Clone`, see [`SimulatableMut`].
+    fn repeat_mut(self, times: usize) -> RepeatMut<Self> {
+        RepeatMut {
+            times,
+            simulate: self,
+        }
+    }
+
+    /// Repeat simulation while the predicate returns `true`.
+    /// The predicate is checked against the simulator before every iteration.
+    fn repeat_while<P>(self, predicate: P) -> RepeatWhile<P, Self> {
+        RepeatWhile {
+            predicate,
+            simulate: self,
+            max_iterations: None,
+        }
+    }
+
+    /// Repeat simulation until the predicate returns `true`.
+    /// The predicate is checked against the simulator before every iteration.
+    fn repeat_until<P>(self, predicate: P) -> RepeatUntil<P, Self> {
+        RepeatUntil {
+            predicate,
+            simulate: self,
+            max_iterations: None,
+        }
+    }
+
+    /// Repeat simulation until the duration has passed,
+    /// see [`RepeatFor`].
+    fn repeat_for(self, duration: Duration) -> RepeatFor<Self> {
+        RepeatFor {
+            duration,
+            simulate: self,
+            max_iterations: None,
+        }
+    }
+
     /// Iterate through an iterator and simulate each item
     /// Self must be an iterator and its item must be `Simulatable`.
     fn sim_iter(self) -> SimIter<Self>
@@ -59,6 +110,7 @@ pub trait Combine: Sized {
     /// Simulate self during an event.
     /// After self is simulated, the *during* event is inverted
     /// and simulated at the end.
+    /// The inverted event is simulated even if self panics or fails.
     fn during<DS>(self, during: DS) -> During<DS, Self>
     where
         DS: Invert + Clone,
@@ -68,11 +120,48 @@ pub trait Combine: Sized {
             simulate: self,
         }
     }
+
+    /// Simulate self only if the predicate returns `true`.
+    /// The predicate is checked against the simulator when simulated.
+    /// Use [`If::or_else`] to simulate something else otherwise.
+    fn when<P>(self, predicate: P) -> If<P, Self> {
+        If {
+            predicate,
+            simulate: self,
+        }
+    }
+
+    /// Press self as a [`Chord`].
+    /// Self must be a tuple, an array or a `Vec` of button-like inputs.
+    fn chord(self) -> Chord<Self> {
+        Chord::new(self)
+    }
+
+    /// Box self so it can be stored with different simulatables.
+    fn boxed<'a, Smltr>(self) -> Box<dyn DynSimulatable<Smltr> + 'a>
+    where
+        Self: Simulatable<Smltr> + 'a,
+    {
+        Box::new(self)
+    }
+
+    /// Box self so it can be stored with different simulatables
+    /// and simulated by reference.
+    fn boxed_mut<'a, Smltr>(self) -> Box<dyn DynSimulatableMut<Smltr> + 'a>
+    where
+        Self: SimulatableMut<Smltr> + 'a,
+    {
+        Box::new(self)
+    }
 }
 
 impl<S> Combine for S {}
 
-/// Thread sleep for amount of time.
+/// Sleep for amount of time.
+///
+/// The sleep is simulated with the simulator's [`Clock`],
+/// so a [`VirtualClock`](crate::clock::VirtualClock) doesn't actually wait
+/// and a [`Cancellable`](crate::cancel::Cancellable) wakes up when cancelled.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Sleep(pub Duration);
 
@@ -82,9 +171,39 @@ impl Sleep {
     }
 }
 
-impl<Smltr> Simulatable<Smltr> for Sleep {
-    fn run_with(self, _: &mut Smltr) {
-        thread::sleep(self.0);
+impl<Smltr> Simulatable<Smltr> for Sleep
+where
+    Smltr: Clock,
+{
+    fn run_with(self, simulator: &mut Smltr) {
+        simulator.sleep(self.0)
+    }
+}
+
+impl<Smltr> TrySimulatable<Smltr, Smltr::Error> for Sleep
+where
+    Smltr: TryClock,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_sleep(self.0).map_err(StepError::new)
+    }
+}
+
+impl<Smltr> SimulatableMut<Smltr> for Sleep
+where
+    Smltr: Clock,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        simulator.sleep(self.0)
+    }
+}
+
+impl<Smltr> TrySimulatableMut<Smltr, Smltr::Error> for Sleep
+where
+    Smltr: TryClock,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_sleep(self.0).map_err(StepError::new)
     }
 }
 
@@ -101,6 +220,9 @@ impl From<Duration> for Sleep {
 }
 
 /// Simulate an input for amount of times
+///
+/// The input is cloned for every iteration,
+/// use [`RepeatMut`] to simulate it by reference instead.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Repeat<S> {
     pub simulate: S,
@@ -111,13 +233,46 @@ impl<S, Smltr> Simulatable<Smltr> for Repeat<S>
 where
     S: Simulatable<Smltr> + Clone,
 {
-    fn run_with(self, simulator: &mut Smltr) {
+    fn run_with(mut self, simulator: &mut Smltr) {
+        self.run_mut(simulator)
+    }
+}
+
+impl<S, Smltr, E> TrySimulatable<Smltr, E> for Repeat<S>
+where
+    S: TrySimulatable<Smltr, E> + Clone,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.try_run_mut(simulator)
+    }
+}
+
+impl<S, Smltr> SimulatableMut<Smltr> for Repeat<S>
+where
+    S: Simulatable<Smltr> + Clone,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
         for _ in 0..self.times {
             self.simulate.clone().run_with(simulator)
         }
     }
 }
 
+impl<S, Smltr, E> TrySimulatableMut<Smltr, E> for Repeat<S>
+where
+    S: TrySimulatable<Smltr, E> + Clone,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        for i in 0..self.times {
+            self.simulate
+                .clone()
+                .try_run_with(simulator)
+                .map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+}
+
 impl<S> fmt::Display for Repeat<S>
 where
     S: fmt::Display,
@@ -127,6 +282,314 @@ where
     }
 }
 
+/// Simulate an input by reference for amount of times,
+/// see [`SimulatableMut`].
+///
+/// ```
+/// use kemuler::{prelude::*, combinator::Call, event_log::EventLog};
+///
+/// let mut s = EventLog::new();
+/// // closures that are not `Clone` can be repeated
+/// let mut count = 0;
+/// Call::new(|_: &mut EventLog| count += 1).repeat_mut(3).run_with(&mut s);
+/// assert_eq!(count, 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RepeatMut<S> {
+    pub simulate: S,
+    pub times: usize,
+}
+
+impl<S, Smltr> Simulatable<Smltr> for RepeatMut<S>
+where
+    S: SimulatableMut<Smltr>,
+{
+    fn run_with(mut self, simulator: &mut Smltr) {
+        self.run_mut(simulator)
+    }
+}
+
+impl<S, Smltr, E> TrySimulatable<Smltr, E> for RepeatMut<S>
+where
+    S: TrySimulatableMut<Smltr, E>,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.try_run_mut(simulator)
+    }
+}
+
+impl<S, Smltr> SimulatableMut<Smltr> for RepeatMut<S>
+where
+    S: SimulatableMut<Smltr>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        for _ in 0..self.times {
+            self.simulate.run_mut(simulator)
+        }
+    }
+}
+
+impl<S, Smltr, E> TrySimulatableMut<Smltr, E> for RepeatMut<S>
+where
+    S: TrySimulatableMut<Smltr, E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        for i in 0..self.times {
+            self.simulate.try_run_mut(simulator).map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+}
+
+impl<S> fmt::Display for RepeatMut<S>
+where
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[for {} times do ({})]", self.times, self.simulate)
+    }
+}
+
+/// Simulate `simulate` by reference for as long as `proceed` allows,
+/// `proceed` is given the simulator and the current iteration.
+fn run_loop<S, Smltr>(
+    simulate: &mut S,
+    simulator: &mut Smltr,
+    mut proceed: impl FnMut(&mut Smltr, usize) -> bool,
+) where
+    S: SimulatableMut<Smltr>,
+{
+    let mut i = 0;
+    while proceed(simulator, i) {
+        simulate.run_mut(simulator);
+        i += 1;
+    }
+}
+
+fn try_run_loop<S, Smltr, E>(
+    simulate: &mut S,
+    simulator: &mut Smltr,
+    mut proceed: impl FnMut(&mut Smltr, usize) -> bool,
+) -> Result<(), StepError<E>>
+where
+    S: TrySimulatableMut<Smltr, E>,
+{
+    let mut i = 0;
+    while proceed(simulator, i) {
+        simulate.try_run_mut(simulator).map_err(|e| e.at(i))?;
+        i += 1;
+    }
+    Ok(())
+}
+
+fn below(max_iterations: Option<usize>, i: usize) -> bool {
+    max_iterations.map_or(true, |max| i < max)
+}
+
+/// Implement the simulatable traits of a loop combinator.
+/// `$proceed` makes the closure that decides whether to do another iteration,
+/// it's made right before the first iteration with the listed fields borrowed.
+macro_rules! loop_impl {
+    (
+        $ty:ident<$($g:ident),*> $(where { $($bound:tt)* })?,
+        |$($field:ident),*| $proceed:expr
+    ) => {
+        impl<$($g,)* Smltr> Simulatable<Smltr> for $ty<$($g),*>
+        where
+            S: SimulatableMut<Smltr>,
+            $($($bound)*)?
+        {
+            fn run_with(mut self, simulator: &mut Smltr) {
+                self.run_mut(simulator)
+            }
+        }
+
+        impl<$($g,)* Smltr, E> TrySimulatable<Smltr, E> for $ty<$($g),*>
+        where
+            S: TrySimulatableMut<Smltr, E>,
+            $($($bound)*)?
+        {
+            fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                self.try_run_mut(simulator)
+            }
+        }
+
+        impl<$($g,)* Smltr> SimulatableMut<Smltr> for $ty<$($g),*>
+        where
+            S: SimulatableMut<Smltr>,
+            $($($bound)*)?
+        {
+            fn run_mut(&mut self, simulator: &mut Smltr) {
+                let $ty { simulate, $($field,)* .. } = self;
+                let proceed = $proceed;
+                run_loop(simulate, simulator, proceed);
+            }
+        }
+
+        impl<$($g,)* Smltr, E> TrySimulatableMut<Smltr, E> for $ty<$($g),*>
+        where
+            S: TrySimulatableMut<Smltr, E>,
+            $($($bound)*)?
+        {
+            fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                let $ty { simulate, $($field,)* .. } = self;
+                let proceed = $proceed;
+                try_run_loop(simulate, simulator, proceed)
+            }
+        }
+    };
+}
+
+/// Simulate an input while the predicate returns `true`
+/// when checked against the simulator before every iteration.
+///
+/// ```
+/// use kemuler::{prelude::*, event_log::EventLog};
+///
+/// let mut s = EventLog::new();
+/// MouseScroll
+///     .scroll_by(0, -1)
+///     .repeat_while(|s: &mut EventLog| s.len() < 3)
+///     .run_with(&mut s);
+/// assert_eq!(s.len(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RepeatWhile<P, S> {
+    pub predicate: P,
+    pub simulate: S,
+    pub max_iterations: Option<usize>,
+}
+
+impl<P, S> RepeatWhile<P, S> {
+    /// Stop after this many iterations even if the predicate still holds.
+    pub fn max_iterations(mut self, max: usize) -> Self {
+        self.max_iterations = Some(max);
+        self
+    }
+}
+
+loop_impl!(
+    RepeatWhile<P, S> where { P: FnMut(&mut Smltr) -> bool, },
+    |predicate, max_iterations| {
+        move |simulator: &mut Smltr, i| below(*max_iterations, i) && predicate(simulator)
+    }
+);
+
+impl<P, S> fmt::Display for RepeatWhile<P, S>
+where
+    P: fmt::Display,
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[while ({}) do ({})]", self.predicate, self.simulate)
+    }
+}
+
+/// Simulate an input until the predicate returns `true`
+/// when checked against the simulator before every iteration.
+///
+/// ```
+/// use kemuler::{prelude::*, state_tracker::StateTracker, event_log::EventLog};
+///
+/// // move down until the cursor reached the bottom
+/// let mut s = StateTracker::with_cursor(EventLog::new(), (0, 0));
+/// MousePosition
+///     .move_by(0, 100)
+///     .repeat_until(|s: &mut StateTracker<EventLog>| s.cursor() == Some((0, 300)))
+///     .max_iterations(10)
+///     .run_with(&mut s);
+/// assert_eq!(s.inner().len(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RepeatUntil<P, S> {
+    pub predicate: P,
+    pub simulate: S,
+    pub max_iterations: Option<usize>,
+}
+
+impl<P, S> RepeatUntil<P, S> {
+    /// Stop after this many iterations even if the predicate never holds.
+    pub fn max_iterations(mut self, max: usize) -> Self {
+        self.max_iterations = Some(max);
+        self
+    }
+}
+
+loop_impl!(
+    RepeatUntil<P, S> where { P: FnMut(&mut Smltr) -> bool, },
+    |predicate, max_iterations| {
+        move |simulator: &mut Smltr, i| below(*max_iterations, i) && !predicate(simulator)
+    }
+);
+
+impl<P, S> fmt::Display for RepeatUntil<P, S>
+where
+    P: fmt::Display,
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[until ({}) do ({})]", self.predicate, self.simulate)
+    }
+}
+
+/// Simulate an input until the duration has passed.
+/// The last iteration may end after the duration.
+///
+/// Time is measured with the simulator's [`Clock`].
+///
+/// ```
+/// use kemuler::{prelude::*, clock::VirtualClock, event_log::EventLog};
+/// use kemuler::utils::time::{millis, secs};
+///
+/// // click every 200ms for 1 second
+/// let mut s = EventLog::with_clock(VirtualClock::new());
+/// MouseButton::Left
+///     .click()
+///     .sleep(millis(200))
+///     .repeat_for(secs(1))
+///     .run_with(&mut s);
+/// assert_eq!(s.presses(MouseButton::Left), 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RepeatFor<S> {
+    pub duration: Duration,
+    pub simulate: S,
+    pub max_iterations: Option<usize>,
+}
+
+impl<S> RepeatFor<S> {
+    /// Stop after this many iterations even if the duration has not passed.
+    pub fn max_iterations(mut self, max: usize) -> Self {
+        self.max_iterations = Some(max);
+        self
+    }
+}
+
+loop_impl!(
+    RepeatFor<S> where { Smltr: Clock, },
+    |duration, max_iterations| {
+        let mut start = None;
+        move |simulator: &mut Smltr, i| {
+            let start = *start.get_or_insert_with(|| simulator.now());
+            below(*max_iterations, i) && simulator.now().saturating_sub(start) < *duration
+        }
+    }
+);
+
+impl<S> fmt::Display for RepeatFor<S>
+where
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[for {} ms do ({})]",
+            self.duration.as_millis(),
+            self.simulate
+        )
+    }
+}
+
 /// Simulate through a tuple starting from `.0`.
 /// Supported size: 0 <= size <= 32
 /// Nest them if you ever need more.
@@ -173,6 +636,63 @@ macro_rules! tuple_impl {
             }
         }
 
+        impl<Smltr, E, $($g,)*> TrySimulatable<Smltr, E> for SimTuple<($($g,)*)>
+        where
+            $(
+                $g: TrySimulatable<Smltr, E>,
+            )*
+        {
+            #[allow(unused)]
+            fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                let inner = self.0;
+                reverse_order!(
+                    $(
+                        tuple_impl!(@nth inner, $n)
+                            .try_run_with(simulator)
+                            .map_err(|e| e.at($n))?;
+                    )*
+                );
+                Ok(())
+            }
+        }
+
+        impl<Smltr, $($g,)*> SimulatableMut<Smltr> for SimTuple<($($g,)*)>
+        where
+            $(
+                $g: SimulatableMut<Smltr>,
+            )*
+        {
+            #[allow(unused)]
+            fn run_mut(&mut self, simulator: &mut Smltr) {
+                let inner = &mut self.0;
+                reverse_order!(
+                    $(
+                        tuple_impl!(@nth inner, $n).run_mut(simulator);
+                    )*
+                );
+            }
+        }
+
+        impl<Smltr, E, $($g,)*> TrySimulatableMut<Smltr, E> for SimTuple<($($g,)*)>
+        where
+            $(
+                $g: TrySimulatableMut<Smltr, E>,
+            )*
+        {
+            #[allow(unused)]
+            fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                let inner = &mut self.0;
+                reverse_order!(
+                    $(
+                        tuple_impl!(@nth inner, $n)
+                            .try_run_mut(simulator)
+                            .map_err(|e| e.at($n))?;
+                    )*
+                );
+                Ok(())
+            }
+        }
+
         impl<$($g,)*> fmt::Display for SimTuple<($($g,)*)>
         where
             $(
@@ -227,6 +747,46 @@ where
     }
 }
 
+impl<I, Smltr, E> TrySimulatable<Smltr, E> for SimIter<I>
+where
+    I: IntoIterator,
+    <I as IntoIterator>::Item: TrySimulatable<Smltr, E>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        for (i, s) in self.iter.into_iter().enumerate() {
+            s.try_run_with(simulator).map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulated by reference when the iterator can be iterated
+/// by mutable reference, such as `Vec` and arrays.
+impl<I, S, Smltr> SimulatableMut<Smltr> for SimIter<I>
+where
+    for<'a> &'a mut I: IntoIterator<Item = &'a mut S>,
+    S: SimulatableMut<Smltr>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        for s in &mut self.iter {
+            s.run_mut(simulator);
+        }
+    }
+}
+
+impl<I, S, Smltr, E> TrySimulatableMut<Smltr, E> for SimIter<I>
+where
+    for<'a> &'a mut I: IntoIterator<Item = &'a mut S>,
+    S: TrySimulatableMut<Smltr, E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        for (i, s) in (&mut self.iter).into_iter().enumerate() {
+            s.try_run_mut(simulator).map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+}
+
 impl<S> fmt::Display for SimIter<S>
 where
     S: fmt::Display,
@@ -236,6 +796,11 @@ where
     }
 }
 
+/// Simulate the *during* event, then `simulate`,
+/// then the inverted *during* event.
+///
+/// The inverted event is always simulated once the *during* event has been,
+/// even when `simulate` panics or fails, so nothing stays held down.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct During<DS, S> {
     during: DS,
@@ -250,8 +815,67 @@ where
 {
     fn run_with(self, simulator: &mut Smltr) {
         self.during.clone().run_with(simulator);
-        self.simulate.run_with(simulator);
-        self.during.invert().run_with(simulator);
+        let mut guard = ReleaseGuard::new(simulator, self.during.invert(), |r, s| r.run_with(s));
+        self.simulate.run_with(guard.simulator());
+    }
+}
+
+impl<DS, S, Smltr, E> TrySimulatable<Smltr, E> for During<DS, S>
+where
+    S: TrySimulatable<Smltr, E>,
+    DS: Invert + TrySimulatable<Smltr, E> + Clone,
+    <DS as Invert>::Output: TrySimulatable<Smltr, E>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.during
+            .clone()
+            .try_run_with(simulator)
+            .map_err(|e| e.at(0))?;
+        // on failure, the error of the inverted event is dropped in favor of
+        // the one that caused it
+        let mut guard = ReleaseGuard::new(simulator, self.during.invert(), |r, s| {
+            let _ = TrySimulatable::<Smltr, E>::try_run_with(r, s);
+        });
+        self.simulate
+            .try_run_with(guard.simulator())
+            .map_err(|e| e.at(1))?;
+        guard.disarm().try_run_with(simulator).map_err(|e| e.at(2))
+    }
+}
+
+impl<DS, S, Smltr> SimulatableMut<Smltr> for During<DS, S>
+where
+    S: SimulatableMut<Smltr>,
+    DS: Invert + Simulatable<Smltr> + Clone,
+    <DS as Invert>::Output: Simulatable<Smltr>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        self.during.clone().run_with(simulator);
+        let release = self.during.clone().invert();
+        let mut guard = ReleaseGuard::new(simulator, release, |r, s| r.run_with(s));
+        self.simulate.run_mut(guard.simulator());
+    }
+}
+
+impl<DS, S, Smltr, E> TrySimulatableMut<Smltr, E> for During<DS, S>
+where
+    S: TrySimulatableMut<Smltr, E>,
+    DS: Invert + TrySimulatable<Smltr, E> + Clone,
+    <DS as Invert>::Output: TrySimulatable<Smltr, E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.during
+            .clone()
+            .try_run_with(simulator)
+            .map_err(|e| e.at(0))?;
+        let release = self.during.clone().invert();
+        let mut guard = ReleaseGuard::new(simulator, release, |r, s| {
+            let _ = TrySimulatable::<Smltr, E>::try_run_with(r, s);
+        });
+        self.simulate
+            .try_run_mut(guard.simulator())
+            .map_err(|e| e.at(1))?;
+        guard.disarm().try_run_with(simulator).map_err(|e| e.at(2))
     }
 }
 
@@ -266,9 +890,17 @@ where
 }
 
 /// Call a simulator with a closure
+///
+/// When fallibly simulated, the closure must return `Result<(), E>`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Call<F>(F);
 
+impl<F> Call<F> {
+    pub fn new(f: F) -> Call<F> {
+        Call(f)
+    }
+}
+
 impl<Smltr, F> Simulatable<Smltr> for Call<F>
 where
     F: FnMut(&mut Smltr),
@@ -278,6 +910,33 @@ where
     }
 }
 
+impl<Smltr, E, F> TrySimulatable<Smltr, E> for Call<F>
+where
+    F: FnMut(&mut Smltr) -> Result<(), E>,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.0(simulator).map_err(StepError::new)
+    }
+}
+
+impl<Smltr, F> SimulatableMut<Smltr> for Call<F>
+where
+    F: FnMut(&mut Smltr),
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        self.0(simulator)
+    }
+}
+
+impl<Smltr, E, F> TrySimulatableMut<Smltr, E> for Call<F>
+where
+    F: FnMut(&mut Smltr) -> Result<(), E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.0(simulator).map_err(StepError::new)
+    }
+}
+
 impl<F> fmt::Display for Call<F>
 where
     F: fmt::Display,
@@ -286,3 +945,378 @@ where
         write!(f, "[call {}]", self.0)
     }
 }
+
+/// Simulate `simulate` only if the predicate returns `true`
+/// when checked against the simulator.
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, state_tracker::StateTracker, event_log::EventLog};
+///
+/// let mut s = StateTracker::new(EventLog::new());
+/// let close_dialog = || {
+///     Key::Escape
+///         .click()
+///         .when(|s: &mut StateTracker<EventLog>| s.is_down(Key::Alt))
+/// };
+///
+/// close_dialog().run_with(&mut s);
+/// Key::Alt.down().run_with(&mut s);
+/// close_dialog().run_with(&mut s);
+///
+/// let log = s.into_inner();
+/// assert_events!(
+///     log, 0,
+///     Key::Alt.down(),
+///     Key::Escape.down(),
+///     Key::Escape.up(),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct If<P, S> {
+    pub predicate: P,
+    pub simulate: S,
+}
+
+impl<P, S> If<P, S> {
+    /// Simulate `otherwise` if the predicate returns `false`.
+    pub fn or_else<O>(self, otherwise: O) -> IfElse<P, S, O> {
+        IfElse {
+            predicate: self.predicate,
+            simulate: self.simulate,
+            otherwise,
+        }
+    }
+}
+
+impl<P, S, Smltr> Simulatable<Smltr> for If<P, S>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: Simulatable<Smltr>,
+{
+    fn run_with(mut self, simulator: &mut Smltr) {
+        if (self.predicate)(simulator) {
+            self.simulate.run_with(simulator)
+        }
+    }
+}
+
+impl<P, S, Smltr, E> TrySimulatable<Smltr, E> for If<P, S>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: TrySimulatable<Smltr, E>,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        if (self.predicate)(simulator) {
+            self.simulate.try_run_with(simulator).map_err(|e| e.at(0))?;
+        }
+        Ok(())
+    }
+}
+
+impl<P, S, Smltr> SimulatableMut<Smltr> for If<P, S>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: SimulatableMut<Smltr>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        if (self.predicate)(simulator) {
+            self.simulate.run_mut(simulator)
+        }
+    }
+}
+
+impl<P, S, Smltr, E> TrySimulatableMut<Smltr, E> for If<P, S>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: TrySimulatableMut<Smltr, E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        if (self.predicate)(simulator) {
+            self.simulate.try_run_mut(simulator).map_err(|e| e.at(0))?;
+        }
+        Ok(())
+    }
+}
+
+impl<P, S> fmt::Display for If<P, S>
+where
+    P: fmt::Display,
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[if ({}), do ({})]", self.predicate, self.simulate)
+    }
+}
+
+/// Simulate `simulate` if the predicate returns `true`
+/// when checked against the simulator, `otherwise` if not.
+/// Made with [`If::or_else`].
+///
+/// When fallibly simulated, a failing `simulate` is step 0
+/// and a failing `otherwise` is step 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IfElse<P, S, O> {
+    pub predicate: P,
+    pub simulate: S,
+    pub otherwise: O,
+}
+
+impl<P, S, O, Smltr> Simulatable<Smltr> for IfElse<P, S, O>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: Simulatable<Smltr>,
+    O: Simulatable<Smltr>,
+{
+    fn run_with(mut self, simulator: &mut Smltr) {
+        if (self.predicate)(simulator) {
+            self.simulate.run_with(simulator)
+        } else {
+            self.otherwise.run_with(simulator)
+        }
+    }
+}
+
+impl<P, S, O, Smltr, E> TrySimulatable<Smltr, E> for IfElse<P, S, O>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: TrySimulatable<Smltr, E>,
+    O: TrySimulatable<Smltr, E>,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        if (self.predicate)(simulator) {
+            self.simulate.try_run_with(simulator).map_err(|e| e.at(0))
+        } else {
+            self.otherwise.try_run_with(simulator).map_err(|e| e.at(1))
+        }
+    }
+}
+
+impl<P, S, O, Smltr> SimulatableMut<Smltr> for IfElse<P, S, O>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: SimulatableMut<Smltr>,
+    O: SimulatableMut<Smltr>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        if (self.predicate)(simulator) {
+            self.simulate.run_mut(simulator)
+        } else {
+            self.otherwise.run_mut(simulator)
+        }
+    }
+}
+
+impl<P, S, O, Smltr, E> TrySimulatableMut<Smltr, E> for IfElse<P, S, O>
+where
+    P: FnMut(&mut Smltr) -> bool,
+    S: TrySimulatableMut<Smltr, E>,
+    O: TrySimulatableMut<Smltr, E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        if (self.predicate)(simulator) {
+            self.simulate.try_run_mut(simulator).map_err(|e| e.at(0))
+        } else {
+            self.otherwise.try_run_mut(simulator).map_err(|e| e.at(1))
+        }
+    }
+}
+
+impl<P, S, O> fmt::Display for IfElse<P, S, O>
+where
+    P: fmt::Display,
+    S: fmt::Display,
+    O: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[if ({}), do ({}), else ({})]",
+            self.predicate, self.simulate, self.otherwise
+        )
+    }
+}
+
+/// One of two simulatables.
+/// Useful to return different simulatables from the branches of an `if` or `match`.
+///
+/// ```
+/// # use kemuler::{event_log::EventLog, assert_events};
+/// use kemuler::{prelude::*, combinator::Either};
+///
+/// fn confirm(accept: bool) -> Either<impl Simulatable<EventLog>, impl Simulatable<EventLog>> {
+///     if accept {
+///         Either::Left(Key::Enter.click())
+///     } else {
+///         Either::Right(Key::Escape.click())
+///     }
+/// }
+///
+/// let mut s = EventLog::new();
+/// confirm(false).run_with(&mut s);
+/// assert_events!(s, 0, Key::Escape.down(), Key::Escape.up(),);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+impl<A, B, Smltr> Simulatable<Smltr> for Either<A, B>
+where
+    A: Simulatable<Smltr>,
+    B: Simulatable<Smltr>,
+{
+    fn run_with(self, simulator: &mut Smltr) {
+        match self {
+            Either::Left(a) => a.run_with(simulator),
+            Either::Right(b) => b.run_with(simulator),
+        }
+    }
+}
+
+impl<A, B, Smltr, E> TrySimulatable<Smltr, E> for Either<A, B>
+where
+    A: TrySimulatable<Smltr, E>,
+    B: TrySimulatable<Smltr, E>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        match self {
+            Either::Left(a) => a.try_run_with(simulator),
+            Either::Right(b) => b.try_run_with(simulator),
+        }
+    }
+}
+
+impl<A, B, Smltr> SimulatableMut<Smltr> for Either<A, B>
+where
+    A: SimulatableMut<Smltr>,
+    B: SimulatableMut<Smltr>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        match self {
+            Either::Left(a) => a.run_mut(simulator),
+            Either::Right(b) => b.run_mut(simulator),
+        }
+    }
+}
+
+impl<A, B, Smltr, E> TrySimulatableMut<Smltr, E> for Either<A, B>
+where
+    A: TrySimulatableMut<Smltr, E>,
+    B: TrySimulatableMut<Smltr, E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        match self {
+            Either::Left(a) => a.try_run_mut(simulator),
+            Either::Right(b) => b.try_run_mut(simulator),
+        }
+    }
+}
+
+impl<A, B> fmt::Display for Either<A, B>
+where
+    A: fmt::Display,
+    B: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Either::Left(a) => a.fmt(f),
+            Either::Right(b) => b.fmt(f),
+        }
+    }
+}
+
+/// `None` simulates nothing.
+impl<S, Smltr> Simulatable<Smltr> for Option<S>
+where
+    S: Simulatable<Smltr>,
+{
+    fn run_with(self, simulator: &mut Smltr) {
+        if let Some(s) = self {
+            s.run_with(simulator)
+        }
+    }
+}
+
+impl<S, Smltr, E> TrySimulatable<Smltr, E> for Option<S>
+where
+    S: TrySimulatable<Smltr, E>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        match self {
+            Some(s) => s.try_run_with(simulator),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<S, Smltr> SimulatableMut<Smltr> for Option<S>
+where
+    S: SimulatableMut<Smltr>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        if let Some(s) = self {
+            s.run_mut(simulator)
+        }
+    }
+}
+
+impl<S, Smltr, E> TrySimulatableMut<Smltr, E> for Option<S>
+where
+    S: TrySimulatableMut<Smltr, E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        match self {
+            Some(s) => s.try_run_mut(simulator),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Simulate the release event when dropped, including while unwinding.
+/// Hold-style combinators use this so a held input is always released.
+pub(crate) struct ReleaseGuard<'a, Smltr, R> {
+    simulator: &'a mut Smltr,
+    release: Option<R>,
+    run: fn(R, &mut Smltr),
+}
+
+impl<'a, Smltr, R> ReleaseGuard<'a, Smltr, R> {
+    pub(crate) fn new(
+        simulator: &'a mut Smltr,
+        release: R,
+        run: fn(R, &mut Smltr),
+    ) -> ReleaseGuard<'a, Smltr, R> {
+        ReleaseGuard {
+            simulator,
+            release: Some(release),
+            run,
+        }
+    }
+
+    pub(crate) fn simulator(&mut self) -> &mut Smltr {
+        self.simulator
+    }
+
+    pub(crate) fn release_mut(&mut self) -> &mut R {
+        self.release
+            .as_mut()
+            .expect("release event is only taken when disarmed")
+    }
+
+    /// Take the release event back without simulating it.
+    pub(crate) fn disarm(mut self) -> R {
+        self.release
+            .take()
+            .expect("release event is only taken once")
+    }
+}
+
+impl<'a, Smltr, R> Drop for ReleaseGuard<'a, Smltr, R> {
+    fn drop(&mut self) {
+        if let Some(release) = self.release.take() {
+            (self.run)(release, self.simulator);
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use super::*;
+use crate::input_event::SetTo;
+
+/// Press button-like inputs together like a keyboard shortcut.
+/// All inputs are pressed in order then released in reverse order.
+///
+/// `inputs` can be a tuple of up to 8 different inputs,
+/// or an array or `Vec` of the same input.
+/// An optional delay set with [`Chord::delay`] is slept
+/// after every press and release but the last.
+/// The simulator only needs a [`Clock`] when the delay is set.
+///
+/// Like [`During`], every pressed input is released even if
+/// the simulation panics or fails.
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, event_log::EventLog};
+///
+/// let mut s = EventLog::new();
+/// let reopen_tab = (Key::Control, Key::Shift, Char('T')).chord();
+/// assert_eq!(reopen_tab.to_string(), "[chord Control+Shift+T]");
+///
+/// reopen_tab.run_with(&mut s);
+/// assert_events!(
+///     s, 0,
+///     Key::Control.down(),
+///     Key::Shift.down(),
+///     Char('T').down(),
+///     Char('T').up(),
+///     Key::Shift.up(),
+///     Key::Control.up(),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Chord<T, Delay = SimTuple<()>> {
+    pub inputs: T,
+    /// Simulated between every press and release,
+    /// nothing until a [`Sleep`] is set with [`Chord::delay`].
+    pub delay: Delay,
+}
+
+impl<T> Chord<T> {
+    pub fn new(inputs: T) -> Chord<T> {
+        Chord {
+            inputs,
+            delay: SimTuple(()),
+        }
+    }
+}
+
+impl<T, Delay> Chord<T, Delay> {
+    /// Sleep for the duration between every press and release.
+    pub fn delay(self, delay: Duration) -> Chord<T, Sleep> {
+        Chord {
+            inputs: self.inputs,
+            delay: Sleep(delay),
+        }
+    }
+}
+
+/// Build the nested `During`s of a chord,
+/// the first input is held during the rest of the chord.
+macro_rules! chord_nest {
+    ($delay:expr; $input:expr) => {
+        During {
+            during: SetTo::new($input, true),
+            simulate: $delay.clone(),
+        }
+    };
+    ($delay:expr; $input:expr, $($rest:expr),+) => {
+        During {
+            during: SetTo::new($input, true),
+            simulate: SimTuple((
+                $delay.clone(),
+                chord_nest!($delay; $($rest),+),
+                $delay.clone(),
+            )),
+        }
+    };
+}
+
+/// Report a failure in the nested `During`s of a chord
+/// at the index of the failing input, like [`try_run_chord`] does.
+/// Each level of nesting adds two steps: the body of the `During`
+/// and the middle of its `(delay, rest, delay)` tuple.
+fn chord_step<E>(mut error: StepError<E>) -> StepError<E> {
+    let index = error.step.iter().take_while(|&&step| step == 1).count() / 2;
+    error.step = vec![index];
+    error
+}
+
+/// implement chord for tuples
+macro_rules! chord_tuple_impl {
+    ($($n:tt => $g:ident),+) => {
+        impl<Smltr, Delay, $($g,)+> Simulatable<Smltr> for Chord<($($g,)+), Delay>
+        where
+            Delay: Simulatable<Smltr> + Clone,
+            $(
+                $g: Clone,
+                Smltr: Simulate<SetTo<$g, bool>>,
+            )+
+        {
+            fn run_with(self, simulator: &mut Smltr) {
+                let delay = self.delay;
+                chord_nest!(delay; $(self.inputs.$n),+).run_with(simulator)
+            }
+        }
+
+        impl<Smltr, E, Delay, $($g,)+> TrySimulatable<Smltr, E> for Chord<($($g,)+), Delay>
+        where
+            Delay: TrySimulatable<Smltr, E> + Clone,
+            $(
+                $g: Clone,
+                Smltr: TrySimulate<SetTo<$g, bool>, Error = E>,
+            )+
+        {
+            fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                let delay = self.delay;
+                chord_nest!(delay; $(self.inputs.$n),+)
+                    .try_run_with(simulator)
+                    .map_err(chord_step)
+            }
+        }
+
+        impl<Smltr, Delay, $($g,)+> SimulatableMut<Smltr> for Chord<($($g,)+), Delay>
+        where
+            Self: Clone + Simulatable<Smltr>,
+        {
+            fn run_mut(&mut self, simulator: &mut Smltr) {
+                self.clone().run_with(simulator)
+            }
+        }
+
+        impl<Smltr, E, Delay, $($g,)+> TrySimulatableMut<Smltr, E> for Chord<($($g,)+), Delay>
+        where
+            Self: Clone + TrySimulatable<Smltr, E>,
+        {
+            fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                self.clone().try_run_with(simulator)
+            }
+        }
+
+        impl<Delay, $($g,)+> fmt::Display for Chord<($($g,)+), Delay>
+        where
+            $($g: fmt::Display,)+
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let names = [$(self.inputs.$n.to_string()),+];
+                write!(f, "[chord {}]", names.join("+"))
+            }
+        }
+    };
+}
+
+chord_tuple_impl!(0 => A);
+chord_tuple_impl!(0 => A, 1 => B);
+chord_tuple_impl!(0 => A, 1 => B, 2 => C);
+chord_tuple_impl!(0 => A, 1 => B, 2 => C, 3 => D);
+chord_tuple_impl!(0 => A, 1 => B, 2 => C, 3 => D, 4 => F);
+chord_tuple_impl!(0 => A, 1 => B, 2 => C, 3 => D, 4 => F, 5 => G);
+chord_tuple_impl!(0 => A, 1 => B, 2 => C, 3 => D, 4 => F, 5 => G, 6 => H);
+chord_tuple_impl!(0 => A, 1 => B, 2 => C, 3 => D, 4 => F, 5 => G, 6 => H, 7 => I);
+
+/// Pressed inputs of a chord that are not released yet
+/// and how to release them.
+struct Pressed<I, Smltr, O> {
+    pressed: Vec<SetTo<I, bool>>,
+    set: fn(&mut Smltr, SetTo<I, bool>) -> O,
+}
+
+/// Release the pressed inputs, last pressed first.
+/// Errors are dropped in favor of the one that caused the release.
+fn release_all<I, Smltr, O>(pressed: Pressed<I, Smltr, O>, simulator: &mut Smltr) {
+    for release in pressed.pressed.into_iter().rev() {
+        let _ = (pressed.set)(simulator, release);
+    }
+}
+
+/// Press every input in order then release them in reverse order,
+/// each input is set with `set`.
+pub(crate) fn run_chord<I, Delay, Smltr>(
+    inputs: &[I],
+    delay: &mut Delay,
+    simulator: &mut Smltr,
+    set: fn(&mut Smltr, SetTo<I, bool>),
+) where
+    I: Clone,
+    Delay: SimulatableMut<Smltr>,
+{
+    let pressed = Pressed {
+        pressed: Vec::new(),
+        set,
+    };
+    let mut guard = ReleaseGuard::new(simulator, pressed, release_all);
+    for (i, input) in inputs.iter().enumerate() {
+        if i != 0 {
+            delay.run_mut(guard.simulator());
+        }
+        let press = SetTo::new(input.clone(), true);
+        set(guard.simulator(), press.clone());
+        guard.release_mut().pressed.push(press.invert());
+    }
+    while !guard.release_mut().pressed.is_empty() {
+        delay.run_mut(guard.simulator());
+        if let Some(release) = guard.release_mut().pressed.pop() {
+            set(guard.simulator(), release);
+        }
+    }
+}
+
+/// Fallible version of [`run_chord`],
+/// a failure is reported at the index of the input,
+/// and a failed delay at the index of the input it follows.
+pub(crate) fn try_run_chord<I, Delay, Smltr, E>(
+    inputs: &[I],
+    delay: &mut Delay,
+    simulator: &mut Smltr,
+    set: fn(&mut Smltr, SetTo<I, bool>) -> Result<(), E>,
+) -> Result<(), StepError<E>>
+where
+    I: Clone,
+    Delay: TrySimulatableMut<Smltr, E>,
+{
+    let pressed = Pressed {
+        pressed: Vec::new(),
+        set,
+    };
+    let mut guard = ReleaseGuard::new(simulator, pressed, release_all);
+    for (i, input) in inputs.iter().enumerate() {
+        if i != 0 {
+            delay
+                .try_run_mut(guard.simulator())
+                .map_err(|e| e.at(i - 1))?;
+        }
+        let press = SetTo::new(input.clone(), true);
+        set(guard.simulator(), press.clone()).map_err(|e| StepError::new(e).at(i))?;
+        guard.release_mut().pressed.push(press.invert());
+    }
+    while !guard.release_mut().pressed.is_empty() {
+        let i = guard.release_mut().pressed.len() - 1;
+        delay.try_run_mut(guard.simulator()).map_err(|e| e.at(i))?;
+        if let Some(release) = guard.release_mut().pressed.pop() {
+            set(guard.simulator(), release).map_err(|e| StepError::new(e).at(i))?;
+        }
+    }
+    Ok(())
+}
+
+fn simulate<T, Smltr>(simulator: &mut Smltr, simulatable: T)
+where
+    Smltr: Simulate<T>,
+{
+    simulator.simulate(simulatable)
+}
+
+fn try_simulate<T, Smltr>(simulator: &mut Smltr, simulatable: T) -> Result<(), Smltr::Error>
+where
+    Smltr: TrySimulate<T>,
+{
+    simulator.try_simulate(simulatable)
+}
+
+/// implement chord for an array-like of the same input
+macro_rules! chord_slice_impl {
+    ($([$($generics:tt)*] $ty:ty;)*) => {
+        $(
+            impl<Smltr, Delay, I $($generics)*> Simulatable<Smltr> for Chord<$ty, Delay>
+            where
+                I: Clone,
+                Delay: SimulatableMut<Smltr>,
+                Smltr: Simulate<SetTo<I, bool>>,
+            {
+                fn run_with(mut self, simulator: &mut Smltr) {
+                    self.run_mut(simulator)
+                }
+            }
+
+            impl<Smltr, E, Delay, I $($generics)*> TrySimulatable<Smltr, E> for Chord<$ty, Delay>
+            where
+                I: Clone,
+                Delay: TrySimulatableMut<Smltr, E>,
+                Smltr: TrySimulate<SetTo<I, bool>, Error = E>,
+            {
+                fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                    self.try_run_mut(simulator)
+                }
+            }
+
+            impl<Smltr, Delay, I $($generics)*> SimulatableMut<Smltr> for Chord<$ty, Delay>
+            where
+                I: Clone,
+                Delay: SimulatableMut<Smltr>,
+                Smltr: Simulate<SetTo<I, bool>>,
+            {
+                fn run_mut(&mut self, simulator: &mut Smltr) {
+                    run_chord(&self.inputs, &mut self.delay, simulator, simulate)
+                }
+            }
+
+            impl<Smltr, E, Delay, I $($generics)*> TrySimulatableMut<Smltr, E> for Chord<$ty, Delay>
+            where
+                I: Clone,
+                Delay: TrySimulatableMut<Smltr, E>,
+                Smltr: TrySimulate<SetTo<I, bool>, Error = E>,
+            {
+                fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+                    try_run_chord(&self.inputs, &mut self.delay, simulator, try_simulate)
+                }
+            }
+
+            impl<Delay, I $($generics)*> fmt::Display for Chord<$ty, Delay>
+            where
+                I: fmt::Display,
+            {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    let names: Vec<String> = self.inputs.iter().map(I::to_string).collect();
+                    write!(f, "[chord {}]", names.join("+"))
+                }
+            }
+        )*
+    };
+}
+
+chord_slice_impl! {
+    [] Vec<I>;
+    [, const N: usize] [I; N];
+}
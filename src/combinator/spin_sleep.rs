@@ -2,6 +2,8 @@ use super::*;
 use core::fmt;
 
 /// Accurate thread sleep for amount of time using [`spin_sleep`](https://crates.io/crates/spin_sleep).
+///
+/// Like [`Sleep`], the sleep is simulated with the simulator's [`Clock`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SpinSleep(pub Duration);
 
@@ -11,9 +13,39 @@ impl SpinSleep {
     }
 }
 
-impl<Smltr> Simulatable<Smltr> for SpinSleep {
-    fn run_with(self, _: &mut Smltr) {
-        ::spin_sleep::sleep(self.0)
+impl<Smltr> Simulatable<Smltr> for SpinSleep
+where
+    Smltr: Clock,
+{
+    fn run_with(self, simulator: &mut Smltr) {
+        simulator.spin_sleep(self.0)
+    }
+}
+
+impl<Smltr> TrySimulatable<Smltr, Smltr::Error> for SpinSleep
+where
+    Smltr: TryClock,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_spin_sleep(self.0).map_err(StepError::new)
+    }
+}
+
+impl<Smltr> SimulatableMut<Smltr> for SpinSleep
+where
+    Smltr: Clock,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        simulator.spin_sleep(self.0)
+    }
+}
+
+impl<Smltr> TrySimulatableMut<Smltr, Smltr::Error> for SpinSleep
+where
+    Smltr: TryClock,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_spin_sleep(self.0).map_err(StepError::new)
     }
 }
 
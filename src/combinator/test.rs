@@ -1,9 +1,13 @@
-use std::time::{Duration, Instant};
+use std::panic::{self, AssertUnwindSafe};
 
+use crate::clock::{Clock, VirtualClock};
+use crate::input_event::SetTo;
 use crate::prelude::*;
-use crate::{assert_events, string_event_logger::StringEventLogger as S};
+use crate::simulator::{Simulate, TrySimulate};
+use crate::utils::time::millis;
+use crate::{assert_events, assert_gap, event_log::EventLog as S};
 
-use super::Sleep;
+use super::{Call, Sleep};
 
 #[test]
 fn combinator_then() {
@@ -11,37 +15,48 @@ fn combinator_then() {
     let x = Key::F1.down().then(Key::F2.down()).then(Key::F3.up());
     x.run_with(&mut s);
     assert_events!(s, 0, Key::F1.down(), Key::F2.down(), Key::F3.up(),);
-    assert_eq!(s.data.len(), 3);
+    assert_eq!(s.events.len(), 3);
 }
 
 #[test]
 fn combinator_sleep() {
-    let mut s = S::new();
-    let sleep_amount = Duration::from_millis(100);
-
-    let previous = Instant::now();
-
-    let x = Sleep(sleep_amount);
+    let mut s = S::with_clock(VirtualClock::new());
+    let x = Sleep(millis(100));
     x.run_with(&mut s);
+    assert_eq!(s.clock.now(), millis(100));
+    assert_eq!(s.events.len(), 0);
+}
 
-    let now = Instant::now();
+#[test]
+#[cfg(feature = "spin_sleep")]
+fn combinator_spin_sleep() {
+    let mut s = S::with_clock(VirtualClock::new());
+    let x = SpinSleep(millis(100));
+    x.run_with(&mut s);
+    assert_eq!(s.clock.now(), millis(100));
+    assert_eq!(s.events.len(), 0);
+}
 
-    let diff = now - previous;
-    let max_acceptatble_deviation = Duration::from_millis(50);
-    let deviated = diff - sleep_amount;
-    assert!(
-        deviated <= max_acceptatble_deviation,
-        "diff is {} millis, deviated by {} millis",
-        diff.as_millis(),
-        deviated.as_millis(),
-    );
-    assert_eq!(s.data.len(), 0);
+#[test]
+fn combinator_sleep_timestamps() {
+    let mut s = S::with_clock(VirtualClock::new());
+    let x = Key::Space
+        .click()
+        .sleep(millis(10))
+        .then(Key::Tab.click())
+        .sleep(millis(5))
+        .repeat(2);
+    x.run_with(&mut s);
+    assert_eq!(s.times, [0, 0, 10, 10, 15, 15, 25, 25].map(millis),);
+    assert_eq!(s.clock.now(), millis(30));
 }
 
 #[test]
-#[ignore = "not implemented"]
-fn combinator_spin_sleep() {
-    todo!()
+fn combinator_sleep_gap() {
+    let mut s = S::with_clock(VirtualClock::new());
+    let x = Key::Space.click().sleep(millis(10)).repeat(2);
+    x.run_with(&mut s);
+    assert_gap!(s, 1, 2, millis(10), millis(10));
 }
 
 #[test]
@@ -58,7 +73,7 @@ fn combinator_repeat() {
         Key::Home.down(),
         Key::Home.down(),
     );
-    assert_eq!(s.data.len(), 5);
+    assert_eq!(s.events.len(), 5);
 }
 
 #[test]
@@ -78,7 +93,7 @@ fn combinator_repeat_complex() {
         Key::Home.down(),
         Key::Tab.up(),
     );
-    assert_eq!(s.data.len(), 8);
+    assert_eq!(s.events.len(), 8);
 }
 
 #[test]
@@ -98,7 +113,7 @@ fn combinator_iter_seq() {
         Key::F4.down(),
         Key::F5.down(),
     );
-    assert_eq!(s.data.len(), 5);
+    assert_eq!(s.events.len(), 5);
 }
 
 #[test]
@@ -118,7 +133,7 @@ fn combinator_seq() {
         MouseButton::Left.up(),
         MousePosition.move_to(25, 10),
     );
-    assert_eq!(s.data.len(), 3);
+    assert_eq!(s.events.len(), 3);
 }
 
 #[test]
@@ -126,7 +141,7 @@ fn combinator_seq_empty() {
     let mut s = S::new();
     let x = ().sim_tuple();
     x.run_with(&mut s);
-    assert_eq!(s.data.len(), 0);
+    assert_eq!(s.events.len(), 0);
 }
 
 #[test]
@@ -142,7 +157,7 @@ fn combinator_during() {
         Key::Tab.up(),
         Key::Alt.up(),
     );
-    assert_eq!(s.data.len(), 4);
+    assert_eq!(s.events.len(), 4);
 }
 
 #[test]
@@ -163,5 +178,323 @@ fn combinator_during_nested() {
         Key::Alt.up(),
         Key::F1.down(),
     );
-    assert_eq!(s.data.len(), 6);
+    assert_eq!(s.events.len(), 6);
+}
+
+/// Logs like `S` but fails the input at index `fail_at`, and only that one.
+struct FailingLogger {
+    log: S,
+    fail_at: usize,
+    attempts: usize,
+}
+
+impl FailingLogger {
+    fn new(fail_at: usize) -> FailingLogger {
+        FailingLogger {
+            log: S::new(),
+            fail_at,
+            attempts: 0,
+        }
+    }
+}
+
+impl<T> TrySimulate<T> for FailingLogger
+where
+    S: Simulate<T>,
+{
+    type Error = &'static str;
+
+    fn try_simulate(&mut self, simulatable: T) -> Result<(), Self::Error> {
+        self.attempts += 1;
+        if self.attempts - 1 == self.fail_at {
+            return Err("device gone");
+        }
+        self.log.simulate(simulatable);
+        Ok(())
+    }
+}
+
+#[test]
+fn combinator_try_seq_stops_at_failure() {
+    let mut s = FailingLogger::new(1);
+    let x = (Key::F1.down(), Key::F2.down(), Key::F3.down()).sim_tuple();
+    let err = x.try_run_with(&mut s).unwrap_err();
+    assert_eq!(err.step, vec![1]);
+    assert_eq!(err.error, "device gone");
+    assert_eq!(err.to_string(), "step [1] failed: device gone");
+    let s = s.log;
+    assert_events!(s, 0, Key::F1.down(),);
+    assert_eq!(s.events.len(), 1);
+}
+
+#[test]
+fn combinator_try_nested_step() {
+    let mut s = FailingLogger::new(5);
+    let x = Key::Tab
+        .click()
+        .during(Key::Alt.down())
+        .repeat(2)
+        .then(Call::new(|_: &mut FailingLogger| Ok(())));
+    let err = x.try_run_with(&mut s).unwrap_err();
+    assert_eq!(err.step, vec![0, 1, 1, 0]);
+    assert_eq!(err.to_string(), "step [0.1.1.0] failed: device gone");
+    let s = s.log;
+    assert_events!(s, 4, Key::Alt.down(), Key::Alt.up(),);
+    assert_eq!(s.events.len(), 6);
+}
+
+#[test]
+fn combinator_try_ok() {
+    let mut s = FailingLogger::new(usize::MAX);
+    let x = [Key::F1, Key::F2].iter().map(|k| k.click()).sim_iter();
+    x.try_run_with(&mut s).unwrap();
+    let s = s.log;
+    assert_events!(
+        s,
+        0,
+        Key::F1.down(),
+        Key::F1.up(),
+        Key::F2.down(),
+        Key::F2.up(),
+    );
+    assert_eq!(s.events.len(), 4);
+}
+
+#[test]
+fn combinator_try_call_error() {
+    let mut s = S::new();
+    let x = Call::new(|_: &mut S| Err("nope"));
+    let err = TrySimulatable::<S, _>::try_run_with(x, &mut s).unwrap_err();
+    assert!(err.step.is_empty());
+    assert_eq!(err.error, "nope");
+}
+
+#[test]
+fn combinator_during_release_on_panic() {
+    let mut s = S::new();
+    let x = Call::new(|_: &mut S| panic!("oh no")).during(Key::Alt.down());
+    let result = panic::catch_unwind(AssertUnwindSafe(|| x.run_with(&mut s)));
+    assert!(result.is_err());
+    assert_events!(s, 0, Key::Alt.down(), Key::Alt.up(),);
+    assert_eq!(s.events.len(), 2);
+}
+
+#[test]
+fn combinator_try_during_release_on_error() {
+    let mut s = FailingLogger::new(usize::MAX);
+    let x = Call::new(|_: &mut FailingLogger| Err("oh no")).during(Key::Alt.down());
+    let err = x.try_run_with(&mut s).unwrap_err();
+    assert_eq!(err.step, vec![1]);
+    let s = s.log;
+    assert_events!(s, 0, Key::Alt.down(), Key::Alt.up(),);
+    assert_eq!(s.events.len(), 2);
+}
+
+#[test]
+fn combinator_if_else() {
+    let mut s = S::new();
+    let x = Key::Escape
+        .click()
+        .when(|s: &mut S| s.presses(Key::F1) > 0)
+        .or_else(Key::F1.click())
+        .repeat(2);
+    x.run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        Key::F1.down(),
+        Key::F1.up(),
+        Key::Escape.down(),
+        Key::Escape.up(),
+    );
+    assert_eq!(s.events.len(), 4);
+}
+
+#[test]
+fn combinator_option() {
+    let mut s = S::new();
+    (Some(Key::F1.click()), None::<SetTo<Key, bool>>)
+        .sim_tuple()
+        .run_with(&mut s);
+    assert_events!(s, 0, Key::F1.down(), Key::F1.up(),);
+    assert_eq!(s.events.len(), 2);
+}
+
+#[test]
+fn combinator_try_else_step() {
+    let mut s = FailingLogger::new(0);
+    let x = Key::F1
+        .down()
+        .when(|_: &mut FailingLogger| false)
+        .or_else(Key::F2.down());
+    let err = x.try_run_with(&mut s).unwrap_err();
+    assert_eq!(err.step, vec![1]);
+}
+
+#[test]
+fn combinator_repeat_while_max_iterations() {
+    let mut s = S::new();
+    Key::F1
+        .click()
+        .repeat_while(|_: &mut S| true)
+        .max_iterations(3)
+        .run_with(&mut s);
+    assert_eq!(s.presses(Key::F1), 3);
+}
+
+#[test]
+fn combinator_repeat_for_virtual_clock() {
+    let mut s = S::with_clock(VirtualClock::new());
+    Key::F1
+        .click()
+        .sleep(millis(30))
+        .repeat_for(millis(100))
+        .run_with(&mut s);
+    assert_eq!(s.presses(Key::F1), 4);
+    assert_eq!(s.now(), millis(120));
+}
+
+#[test]
+fn combinator_try_repeat_until_step() {
+    let mut s = FailingLogger::new(4);
+    let x = Key::F1
+        .click()
+        .repeat_until(|s: &mut FailingLogger| s.log.presses(Key::F1) == 5);
+    let err = x.try_run_with(&mut s).unwrap_err();
+    assert_eq!(err.step, vec![2, 0]);
+}
+
+#[test]
+fn combinator_chord_array_delay() {
+    let mut s = S::with_clock(VirtualClock::new());
+    let x = [Key::Control, Key::Alt, Key::Delete]
+        .chord()
+        .delay(millis(10));
+    assert_eq!(x.to_string(), "[chord Control+Alt+Delete]");
+    x.run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        Key::Control.down(),
+        Key::Alt.down(),
+        Key::Delete.down(),
+        Key::Delete.up(),
+        Key::Alt.up(),
+        Key::Control.up(),
+    );
+    assert_eq!(s.events.len(), 6);
+    assert_eq!(s.now(), millis(50));
+}
+
+#[test]
+fn combinator_chord_tuple_delay() {
+    let mut s = S::with_clock(VirtualClock::new());
+    (Key::Shift, MouseButton::Left)
+        .chord()
+        .delay(millis(10))
+        .run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        Key::Shift.down(),
+        MouseButton::Left.down(),
+        MouseButton::Left.up(),
+        Key::Shift.up(),
+    );
+    assert_eq!(s.now(), millis(30));
+}
+
+#[test]
+fn combinator_try_chord_step() {
+    let mut s = FailingLogger::new(2);
+    let err = vec![Key::Control, Key::Shift, Key::Escape]
+        .chord()
+        .try_run_with(&mut s)
+        .unwrap_err();
+    assert_eq!(err.step, vec![2]);
+    let s = s.log;
+    assert_events!(
+        s,
+        0,
+        Key::Control.down(),
+        Key::Shift.down(),
+        Key::Shift.up(),
+        Key::Control.up(),
+    );
+    assert_eq!(s.events.len(), 4);
+
+    // tuples report the same step
+    let mut s = FailingLogger::new(2);
+    let err = (Key::Control, Key::Shift, Key::Escape)
+        .chord()
+        .try_run_with(&mut s)
+        .unwrap_err();
+    assert_eq!(err.step, vec![2]);
+    let s = s.log;
+    assert_events!(
+        s,
+        0,
+        Key::Control.down(),
+        Key::Shift.down(),
+        Key::Shift.up(),
+        Key::Control.up(),
+    );
+    assert_eq!(s.events.len(), 4);
+}
+
+/// A user simulatable that is only `Simulatable + Clone`.
+#[derive(Clone)]
+struct Press(Key);
+
+impl Simulatable<S> for Press {
+    fn run_with(self, simulator: &mut S) {
+        self.0.down().run_with(simulator)
+    }
+}
+
+impl crate::input_event::Invert for Press {
+    type Output = SetTo<Key, bool>;
+
+    fn invert(self) -> Self::Output {
+        self.0.up()
+    }
+}
+
+#[test]
+fn combinator_repeat_clone() {
+    let mut s = S::new();
+    [Key::F1, Key::F2]
+        .iter()
+        .map(|k| k.click())
+        .sim_iter()
+        .repeat(2)
+        .run_with(&mut s);
+    assert_eq!(s.presses(Key::F1), 2);
+    assert_eq!(s.presses(Key::F2), 2);
+
+    let mut s = S::new();
+    Press(Key::F3).repeat(2).run_with(&mut s);
+    Key::Tab.click().during(Press(Key::Alt)).run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        Key::F3.down(),
+        Key::F3.down(),
+        Key::Alt.down(),
+        Key::Tab.down(),
+        Key::Tab.up(),
+        Key::Alt.up(),
+    );
+}
+
+#[test]
+fn combinator_repeat_mut() {
+    let mut s = S::new();
+    let mut count = 0;
+    Call::new(|_: &mut S| count += 1)
+        .then(Key::F1.click())
+        .repeat_mut(3)
+        .run_with(&mut s);
+    assert_eq!(count, 3);
+    assert_eq!(s.presses(Key::F1), 3);
 }
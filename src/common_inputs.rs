@@ -69,6 +69,29 @@ macro_rules! button_like_impl_body {
     };
 }
 
+/// A key by what it does, whatever the layout is.
+///
+/// `A` to `Z` and `Digit0` to `Digit9` are the keys labeled with that letter or digit
+/// on the current layout, like Windows virtual-key codes.
+/// They are for shortcuts that go by the key rather than the character it types,
+/// type text and symbols with [`Char`].
+///
+/// `Shift`, `Control`, `Alt` and `Meta` are generic modifiers,
+/// the simulator presses whichever side it likes, usually the left one.
+/// The sided modifiers like `LeftShift` and `RightAlt` press the key on that side,
+/// for applications that tell them apart.
+/// `RightAlt` is AltGr on layouts that have it.
+///
+/// ```
+/// use kemuler::{prelude::*, shortcut::Shortcut};
+///
+/// let lock = Shortcut::parse("Super+L").unwrap();
+/// assert_eq!(lock.to_string(), "Meta+L");
+/// let euro = Shortcut::parse("AltGr+e").unwrap();
+/// assert_eq!(euro.chords, [[Key::RightAlt.into(), Char('e').into()]]);
+/// let copy = Shortcut::parse("Ctrl+KeyC").unwrap();
+/// assert_eq!(copy.chords, [[Key::Control.into(), Key::C.into()]]);
+/// ```
 #[rustfmt::skip]
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -77,18 +100,49 @@ pub enum Key {
     Alt,
     Shift,
     Control,
+    /// windows key on Windows, super key on Linux (command key on macOS)
+    Meta,
+
+    LeftShift, RightShift,
+    LeftControl, RightControl,
+    LeftAlt,
+    /// AltGr on layouts that have it
+    RightAlt,
+    LeftMeta, RightMeta,
+
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+
+    Digit0, Digit1, Digit2, Digit3, Digit4,
+    Digit5, Digit6, Digit7, Digit8, Digit9,
 
     F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
 
-    CapsLock,
+    CapsLock, NumLock, ScrollLock,
 
     End, Home, PageUp, PageDown,
 
     Escape, Enter, Space, Tab,
 
-    Backspace, Delete,
+    Backspace, Delete, Insert,
 
     UpArrow, DownArrow, LeftArrow, RightArrow,
+
+    PrintScreen, Pause,
+    /// the context menu key
+    Menu,
+
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4,
+    Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide,
+    NumpadDecimal, NumpadEnter,
+
+    VolumeUp, VolumeDown, VolumeMute,
+    MediaPlayPause, MediaStop, MediaNext, MediaPrevious,
+
+    BrowserBack, BrowserForward, BrowserRefresh, BrowserStop,
+    BrowserSearch, BrowserFavorites, BrowserHome,
 }
 
 impl Key {
@@ -101,6 +155,141 @@ impl fmt::Display for Key {
     }
 }
 
+/// A key by its position on the keyboard, named after what it is on a US keyboard.
+/// The names follow the `code` values of the W3C UI Events specification.
+///
+/// Unlike [`Key`] and [`Char`], this is the same key whatever the layout is,
+/// like `KeyQ` is the key that types `a` on French layouts.
+/// Useful for games and shortcuts that go by where keys are.
+/// See [`layout`](crate::layout) for what the keys type.
+///
+/// This covers the main block, function, navigation and numpad keys.
+/// Media and browser keys are left out since they have no fixed position,
+/// use [`Key`] for them.
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, event_log::EventLog};
+///
+/// let mut s = EventLog::new();
+/// // forward in most games, whatever the layout
+/// PhysicalKey::KeyW.click().run_with(&mut s);
+/// assert_events!(s, 0, PhysicalKey::KeyW.down(), PhysicalKey::KeyW.up(),);
+/// ```
+#[rustfmt::skip]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PhysicalKey {
+    Backquote,
+    Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9, Digit0,
+    Minus, Equal,
+    KeyQ, KeyW, KeyE, KeyR, KeyT, KeyY, KeyU, KeyI, KeyO, KeyP,
+    BracketLeft, BracketRight,
+    KeyA, KeyS, KeyD, KeyF, KeyG, KeyH, KeyJ, KeyK, KeyL,
+    Semicolon, Quote,
+    /// The key above Enter on ANSI keyboards, left of Enter on ISO keyboards.
+    Backslash,
+    /// The key right of left Shift on ISO keyboards.
+    IntlBackslash,
+    KeyZ, KeyX, KeyC, KeyV, KeyB, KeyN, KeyM,
+    Comma, Period, Slash,
+    Space, Enter, Tab,
+
+    Escape, Backspace, CapsLock,
+    ShiftLeft, ShiftRight, ControlLeft, ControlRight,
+    AltLeft, AltRight, MetaLeft, MetaRight,
+    ContextMenu,
+    ArrowUp, ArrowDown, ArrowLeft, ArrowRight,
+
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    PrintScreen, ScrollLock, Pause,
+    Insert, Delete, Home, End, PageUp, PageDown,
+
+    NumLock,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4,
+    Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide,
+    NumpadDecimal, NumpadEnter,
+}
+
+impl PhysicalKey {
+    button_like_impl_body! {}
+}
+
+impl fmt::Display for PhysicalKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// What the code of a [`RawKey`] means.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CodeSpace {
+    /// Linux input event codes like `KEY_A`, from `linux/input-event-codes.h`
+    LinuxEvdev,
+    /// X11 keysyms like `XK_a`, from `X11/keysymdef.h`
+    X11Keysym,
+    /// USB HID usages, the usage page in the high 16 bits and the usage id in the low 16 bits,
+    /// like `0x0007_0004` for the A key
+    UsbHid,
+    /// Windows virtual-key codes like `VK_A`
+    WindowsVk,
+}
+
+/// A key by its code, for keys that [`Key`] and [`PhysicalKey`] don't name.
+///
+/// Simulators only accept the code spaces they understand,
+/// like a Linux simulator may accept evdev codes but not Windows virtual-key codes.
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, event_log::EventLog};
+///
+/// // KEY_MICMUTE
+/// let mic_mute = RawKey::linux_evdev(248);
+/// assert_eq!(mic_mute.to_string(), "LinuxEvdev(0xf8)");
+///
+/// let mut s = EventLog::new();
+/// mic_mute.click().run_with(&mut s);
+/// assert_events!(s, 0, mic_mute.down(), mic_mute.up(),);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawKey {
+    pub code: u32,
+    pub space: CodeSpace,
+}
+
+impl RawKey {
+    pub fn new(code: u32, space: CodeSpace) -> RawKey {
+        RawKey { code, space }
+    }
+
+    pub fn linux_evdev(code: u32) -> RawKey {
+        RawKey::new(code, CodeSpace::LinuxEvdev)
+    }
+
+    pub fn x11_keysym(code: u32) -> RawKey {
+        RawKey::new(code, CodeSpace::X11Keysym)
+    }
+
+    pub fn usb_hid(code: u32) -> RawKey {
+        RawKey::new(code, CodeSpace::UsbHid)
+    }
+
+    pub fn windows_vk(code: u32) -> RawKey {
+        RawKey::new(code, CodeSpace::WindowsVk)
+    }
+
+    button_like_impl_body! {}
+}
+
+impl fmt::Display for RawKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}({:#x})", self.space, self.code)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct MousePosition;
 
@@ -205,12 +394,59 @@ impl Char {
     button_like_impl_body! {}
 }
 
+impl fmt::Display for Char {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl From<char> for Char {
     fn from(value: char) -> Self {
         Char(value)
     }
 }
 
+/// Any button-like input from this module.
+/// Useful to keep different kind of buttons together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ButtonLike {
+    Key(Key),
+    MouseButton(MouseButton),
+    Char(Char),
+    PhysicalKey(PhysicalKey),
+    RawKey(RawKey),
+}
+
+impl From<Key> for ButtonLike {
+    fn from(value: Key) -> Self {
+        ButtonLike::Key(value)
+    }
+}
+
+impl From<MouseButton> for ButtonLike {
+    fn from(value: MouseButton) -> Self {
+        ButtonLike::MouseButton(value)
+    }
+}
+
+impl From<Char> for ButtonLike {
+    fn from(value: Char) -> Self {
+        ButtonLike::Char(value)
+    }
+}
+
+impl From<PhysicalKey> for ButtonLike {
+    fn from(value: PhysicalKey) -> Self {
+        ButtonLike::PhysicalKey(value)
+    }
+}
+
+impl From<RawKey> for ButtonLike {
+    fn from(value: RawKey) -> Self {
+        ButtonLike::RawKey(value)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StrSimTuple<'a>(pub &'a str);
 
@@ -227,6 +463,11 @@ impl<'a> StrSimTuple<'a> {
     pub fn execute(self) -> Execute<Self> {
         Execute { input: self }
     }
+
+    /// Type the string with key presses, see [`TypeText`](crate::typing::TypeText).
+    pub fn type_text(self) -> crate::typing::TypeText {
+        crate::typing::TypeText::new(self.0)
+    }
 }
 
 impl<'a> From<&'a str> for StrSimTuple<'a> {
@@ -0,0 +1,156 @@
+//! Linux evdev codes of keys and mouse buttons,
+//! the `KEY_*` and `BTN_*` codes from `linux/input-event-codes.h`.
+//!
+//! Conversions go both ways with `TryFrom`.
+//! The generic modifiers like [`Key::Shift`] convert to the code of the left one,
+//! which converts back to the sided key like [`Key::LeftShift`].
+//!
+//! ```
+//! use kemuler::{prelude::*, evdev::EvdevCode};
+//!
+//! assert_eq!(EvdevCode::try_from(Key::Enter), Ok(EvdevCode(28)));
+//! assert_eq!(EvdevCode::try_from(MouseButton::Left), Ok(EvdevCode(0x110)));
+//! assert_eq!(Key::try_from(EvdevCode(125)), Ok(Key::LeftMeta));
+//! assert_eq!(EvdevCode::try_from(Key::Meta), Ok(EvdevCode(125)));
+//!
+//! // a key kemuler doesn't name
+//! assert!(Key::try_from(EvdevCode(248)).is_err());
+//! assert_eq!(RawKey::from(EvdevCode(248)), RawKey::linux_evdev(248));
+//! ```
+
+use std::{error::Error, fmt};
+
+use crate::common_inputs::{CodeSpace, Key, MouseButton, RawKey};
+
+#[cfg(test)]
+mod test;
+
+/// A Linux evdev key or button code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EvdevCode(pub u16);
+
+/// Keys and their codes, sided keys come before the generic ones
+/// so codes convert back to the sided keys.
+/// [`Key::Menu`] is `KEY_COMPOSE` (127), the context menu key on PC keyboards,
+/// not `KEY_MENU` (139).
+#[rustfmt::skip]
+const KEYS: &[(Key, u16)] = {
+    use Key::*;
+    &[
+        (Escape, 1), (Backspace, 14), (Tab, 15), (Enter, 28), (Space, 57),
+        (LeftControl, 29), (LeftShift, 42), (RightShift, 54), (LeftAlt, 56),
+        (RightControl, 97), (RightAlt, 100), (LeftMeta, 125), (RightMeta, 126),
+        (Q, 16), (W, 17), (E, 18), (R, 19), (T, 20),
+        (Y, 21), (U, 22), (I, 23), (O, 24), (P, 25),
+        (A, 30), (S, 31), (D, 32), (F, 33), (G, 34),
+        (H, 35), (J, 36), (K, 37), (L, 38),
+        (Z, 44), (X, 45), (C, 46), (V, 47), (B, 48), (N, 49), (M, 50),
+        (Digit1, 2), (Digit2, 3), (Digit3, 4), (Digit4, 5), (Digit5, 6),
+        (Digit6, 7), (Digit7, 8), (Digit8, 9), (Digit9, 10), (Digit0, 11),
+        (CapsLock, 58), (NumLock, 69), (ScrollLock, 70),
+        (F1, 59), (F2, 60), (F3, 61), (F4, 62), (F5, 63), (F6, 64),
+        (F7, 65), (F8, 66), (F9, 67), (F10, 68), (F11, 87), (F12, 88),
+        (F13, 183), (F14, 184), (F15, 185), (F16, 186), (F17, 187), (F18, 188),
+        (F19, 189), (F20, 190), (F21, 191), (F22, 192), (F23, 193), (F24, 194),
+        (Numpad7, 71), (Numpad8, 72), (Numpad9, 73), (NumpadSubtract, 74),
+        (Numpad4, 75), (Numpad5, 76), (Numpad6, 77), (NumpadAdd, 78),
+        (Numpad1, 79), (Numpad2, 80), (Numpad3, 81), (Numpad0, 82),
+        (NumpadDecimal, 83), (NumpadMultiply, 55), (NumpadEnter, 96), (NumpadDivide, 98),
+        (PrintScreen, 99), (Home, 102), (UpArrow, 103), (PageUp, 104),
+        (LeftArrow, 105), (RightArrow, 106), (End, 107), (DownArrow, 108),
+        (PageDown, 109), (Insert, 110), (Delete, 111), (Pause, 119), (Menu, 127),
+        (VolumeMute, 113), (VolumeDown, 114), (VolumeUp, 115),
+        (MediaNext, 163), (MediaPlayPause, 164), (MediaPrevious, 165), (MediaStop, 166),
+        (BrowserStop, 128), (BrowserFavorites, 156), (BrowserBack, 158),
+        (BrowserForward, 159), (BrowserHome, 172), (BrowserRefresh, 173),
+        (BrowserSearch, 217),
+        (Control, 29), (Shift, 42), (Alt, 56), (Meta, 125),
+    ]
+};
+
+#[rustfmt::skip]
+const MOUSE_BUTTONS: &[(MouseButton, u16)] = &[
+    (MouseButton::Left, 0x110),
+    (MouseButton::Right, 0x111),
+    (MouseButton::Middle, 0x112),
+];
+
+fn to_code<I: PartialEq>(table: &[(I, u16)], input: I) -> Result<EvdevCode, UnmappedError> {
+    table
+        .iter()
+        .find(|(i, _)| *i == input)
+        .map(|(_, code)| EvdevCode(*code))
+        .ok_or(UnmappedError)
+}
+
+fn from_code<I: Copy>(table: &[(I, u16)], code: EvdevCode) -> Result<I, UnmappedError> {
+    table
+        .iter()
+        .find(|(_, c)| *c == code.0)
+        .map(|(input, _)| *input)
+        .ok_or(UnmappedError)
+}
+
+impl TryFrom<Key> for EvdevCode {
+    type Error = UnmappedError;
+
+    fn try_from(value: Key) -> Result<Self, Self::Error> {
+        to_code(KEYS, value)
+    }
+}
+
+impl TryFrom<EvdevCode> for Key {
+    type Error = UnmappedError;
+
+    fn try_from(value: EvdevCode) -> Result<Self, Self::Error> {
+        from_code(KEYS, value)
+    }
+}
+
+impl TryFrom<MouseButton> for EvdevCode {
+    type Error = UnmappedError;
+
+    fn try_from(value: MouseButton) -> Result<Self, Self::Error> {
+        to_code(MOUSE_BUTTONS, value)
+    }
+}
+
+impl TryFrom<EvdevCode> for MouseButton {
+    type Error = UnmappedError;
+
+    fn try_from(value: EvdevCode) -> Result<Self, Self::Error> {
+        from_code(MOUSE_BUTTONS, value)
+    }
+}
+
+impl From<EvdevCode> for RawKey {
+    fn from(value: EvdevCode) -> Self {
+        RawKey::linux_evdev(value.0.into())
+    }
+}
+
+/// Only raw keys in [`CodeSpace::LinuxEvdev`] that fit in `u16` convert.
+impl TryFrom<RawKey> for EvdevCode {
+    type Error = UnmappedError;
+
+    fn try_from(value: RawKey) -> Result<Self, Self::Error> {
+        match value.space {
+            CodeSpace::LinuxEvdev => u16::try_from(value.code)
+                .map(EvdevCode)
+                .map_err(|_| UnmappedError),
+            _ => Err(UnmappedError),
+        }
+    }
+}
+
+/// An input has no evdev code or an evdev code has no input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnmappedError;
+
+impl fmt::Display for UnmappedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no mapping between the input and an evdev code")
+    }
+}
+
+impl Error for UnmappedError {}
@@ -0,0 +1,105 @@
+use crate::prelude::*;
+
+use super::*;
+
+/// Lists every `Key` in `ALL_KEYS`.
+/// The match has no wildcard, so a new variant doesn't compile until it's listed,
+/// then `evdev_every_key` fails until it's in `KEYS`.
+macro_rules! all_keys {
+    ($($key:ident,)+) => {
+        const ALL_KEYS: &[Key] = &[$(Key::$key),+];
+
+        #[allow(dead_code)]
+        fn is_listed(key: Key) {
+            match key {
+                $(Key::$key)|+ => {}
+            }
+        }
+    };
+}
+
+#[rustfmt::skip]
+all_keys! {
+    Alt, Shift, Control, Meta,
+    LeftShift, RightShift, LeftControl, RightControl,
+    LeftAlt, RightAlt, LeftMeta, RightMeta,
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Digit0, Digit1, Digit2, Digit3, Digit4,
+    Digit5, Digit6, Digit7, Digit8, Digit9,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    CapsLock, NumLock, ScrollLock,
+    End, Home, PageUp, PageDown,
+    Escape, Enter, Space, Tab,
+    Backspace, Delete, Insert,
+    UpArrow, DownArrow, LeftArrow, RightArrow,
+    PrintScreen, Pause, Menu,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4,
+    Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide,
+    NumpadDecimal, NumpadEnter,
+    VolumeUp, VolumeDown, VolumeMute,
+    MediaPlayPause, MediaStop, MediaNext, MediaPrevious,
+    BrowserBack, BrowserForward, BrowserRefresh, BrowserStop,
+    BrowserSearch, BrowserFavorites, BrowserHome,
+}
+
+/// The sided key a generic modifier converts back to.
+fn sided(key: Key) -> Key {
+    match key {
+        Key::Shift => Key::LeftShift,
+        Key::Control => Key::LeftControl,
+        Key::Alt => Key::LeftAlt,
+        Key::Meta => Key::LeftMeta,
+        key => key,
+    }
+}
+
+#[test]
+fn evdev_every_key() {
+    for key in ALL_KEYS {
+        let code = EvdevCode::try_from(*key).unwrap_or_else(|_| panic!("{key}"));
+        assert_eq!(Key::try_from(code), Ok(sided(*key)), "{key}");
+    }
+    for button in [MouseButton::Left, MouseButton::Middle, MouseButton::Right] {
+        let code = EvdevCode::try_from(button).unwrap();
+        assert_eq!(MouseButton::try_from(code), Ok(button));
+    }
+}
+
+#[test]
+fn evdev_every_code() {
+    let mut keys = 0;
+    let mut buttons = 0;
+    for code in 0..=u16::MAX {
+        let code = EvdevCode(code);
+        if let Ok(key) = Key::try_from(code) {
+            assert_eq!(EvdevCode::try_from(key), Ok(code), "{key}");
+            keys += 1;
+        }
+        if let Ok(button) = MouseButton::try_from(code) {
+            assert_eq!(EvdevCode::try_from(button), Ok(code), "{button}");
+            buttons += 1;
+        }
+    }
+    // every key but the generic modifiers has its own code
+    assert_eq!(keys, ALL_KEYS.len() - 4);
+    assert_eq!(buttons, 3);
+}
+
+#[test]
+fn evdev_raw_key() {
+    assert_eq!(
+        EvdevCode::try_from(RawKey::linux_evdev(248)),
+        Ok(EvdevCode(248))
+    );
+    assert_eq!(
+        EvdevCode::try_from(RawKey::windows_vk(0x41)),
+        Err(UnmappedError)
+    );
+    assert_eq!(
+        EvdevCode::try_from(RawKey::linux_evdev(0x1_0000)),
+        Err(UnmappedError)
+    );
+}
@@ -0,0 +1,416 @@
+//! Simulator that doesn't simulate anything but logs the inputs.
+
+use std::{convert::Infallible, fmt, time::Duration};
+
+use crate::{
+    cancel::{CancelError, CancelHandle, Cancelled},
+    clock::{Clock, RealClock, TryClock},
+    common_inputs::{
+        ButtonLike, Char, Key, MouseButton, MousePosition, MouseScroll, PhysicalKey, RawKey,
+        StrSimTuple,
+    },
+    input_event::*,
+    simulator::{Simulate, TrySimulate},
+};
+
+#[macro_export]
+macro_rules! assert_event {
+    ($logger:ident, $idx:expr, $event:expr) => {
+        ::std::assert_eq!(
+            $logger.events[$idx],
+            $crate::event_log::Event::from($event),
+            "Assert actual event (left) equals expected event (right) at index {}",
+            $idx
+        );
+    };
+}
+
+#[macro_export]
+macro_rules! assert_events {
+    (
+        $logger:ident,
+        $start_idx:expr,
+        $(
+            $event:expr,
+        )*
+    ) => {
+        $crate::assert_events!(@assert_event $logger, $start_idx, $($event,)*);
+    };
+    (@assert_event $logger:ident, $at:expr, $event:expr,) => {
+        $crate::assert_event!(
+            $logger, $at, $event
+        );
+    };
+    (@assert_event $logger:ident, $at:expr, $event:expr, $($next_events:expr,)*) => {
+        $crate::assert_event!(
+            $logger, $at, $event
+        );
+        $crate::assert_events!(
+            $logger, $at + 1, $($next_events,)*
+        );
+    };
+}
+
+/// Assert the gap of time between two logged events, with an optional tolerance.
+///
+/// ```
+/// # use kemuler::assert_gap;
+/// use kemuler::{prelude::*, clock::VirtualClock, event_log::EventLog, utils::time::millis};
+///
+/// let mut s = EventLog::with_clock(VirtualClock::new());
+/// Key::Space.click().sleep(millis(10)).repeat(2).run_with(&mut s);
+///
+/// // from the second event to the third event
+/// assert_gap!(s, 1, 2, millis(10));
+/// assert_gap!(s, 0, 3, millis(11), millis(2));
+/// ```
+#[macro_export]
+macro_rules! assert_gap {
+    ($logger:ident, $from:expr, $to:expr, $gap:expr) => {
+        $crate::assert_gap!($logger, $from, $to, $gap, ::std::time::Duration::ZERO)
+    };
+    ($logger:ident, $from:expr, $to:expr, $gap:expr, $tolerance:expr) => {{
+        let (from, to): (usize, usize) = ($from, $to);
+        let (gap, tolerance): (::std::time::Duration, ::std::time::Duration) = ($gap, $tolerance);
+        let actual = $logger.times[to]
+            .checked_sub($logger.times[from])
+            .expect("event `to` must not be logged before event `from`");
+        let deviation = if actual > gap {
+            actual - gap
+        } else {
+            gap - actual
+        };
+        ::std::assert!(
+            deviation <= tolerance,
+            "Assert gap from event {} to event {} is {:?} (tolerance {:?}), actual gap is {:?}",
+            from,
+            to,
+            gap,
+            tolerance,
+            actual,
+        );
+    }};
+}
+
+/// Assert the time an event was logged at, with an optional tolerance.
+///
+/// ```
+/// # use kemuler::assert_time;
+/// use kemuler::{prelude::*, clock::VirtualClock, event_log::EventLog, utils::time::millis};
+///
+/// let mut s = EventLog::with_clock(VirtualClock::new());
+/// Sleep(millis(10)).then(Key::Space.click()).run_with(&mut s);
+///
+/// assert_time!(s, 0, millis(10));
+/// assert_time!(s, 1, millis(9), millis(1));
+/// ```
+#[macro_export]
+macro_rules! assert_time {
+    ($logger:ident, $idx:expr, $at:expr) => {
+        $crate::assert_time!($logger, $idx, $at, ::std::time::Duration::ZERO)
+    };
+    ($logger:ident, $idx:expr, $at:expr, $tolerance:expr) => {{
+        let idx: usize = $idx;
+        let (at, tolerance): (::std::time::Duration, ::std::time::Duration) = ($at, $tolerance);
+        let actual = $logger.times[idx];
+        let deviation = if actual > at {
+            actual - at
+        } else {
+            at - actual
+        };
+        ::std::assert!(
+            deviation <= tolerance,
+            "Assert event {} is logged at {:?} (tolerance {:?}), actual time is {:?}",
+            idx,
+            at,
+            tolerance,
+            actual,
+        );
+    }};
+}
+
+/// An input event logged by [`EventLog`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Event {
+    Key(SetTo<Key, bool>),
+    MouseButton(SetTo<MouseButton, bool>),
+    Char(SetTo<Char, bool>),
+    PhysicalKey(SetTo<PhysicalKey, bool>),
+    RawKey(SetTo<RawKey, bool>),
+    MoveTo(SetTo<MousePosition, (i32, i32)>),
+    MoveBy(ChangeBy<MousePosition, (i32, i32)>),
+    Scroll(ChangeBy<MouseScroll, (i32, i32)>),
+    Text(Execute<String>),
+    /// Any other event, logged with its `Debug` representation
+    /// by [`EventLog::push_other`].
+    Other(String),
+}
+
+impl Event {
+    /// The button and its state, if this event sets a button.
+    pub fn button(&self) -> Option<(ButtonLike, bool)> {
+        match self {
+            Event::Key(e) => Some((e.input.into(), e.to)),
+            Event::MouseButton(e) => Some((e.input.into(), e.to)),
+            Event::Char(e) => Some((e.input.into(), e.to)),
+            Event::PhysicalKey(e) => Some((e.input.into(), e.to)),
+            Event::RawKey(e) => Some((e.input.into(), e.to)),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_from_for_event {
+    ($( $ty:ty => $variant:ident, )*) => {
+        $(
+            impl From<$ty> for Event {
+                fn from(value: $ty) -> Self {
+                    Event::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_event! {
+    SetTo<Key, bool> => Key,
+    SetTo<MouseButton, bool> => MouseButton,
+    SetTo<Char, bool> => Char,
+    SetTo<PhysicalKey, bool> => PhysicalKey,
+    SetTo<RawKey, bool> => RawKey,
+    SetTo<MousePosition, (i32, i32)> => MoveTo,
+    ChangeBy<MousePosition, (i32, i32)> => MoveBy,
+    ChangeBy<MouseScroll, (i32, i32)> => Scroll,
+    Execute<String> => Text,
+}
+
+impl<'a> From<Execute<StrSimTuple<'a>>> for Event {
+    fn from(value: Execute<StrSimTuple<'a>>) -> Self {
+        Event::Text(Execute::new(value.input.0.to_owned()))
+    }
+}
+
+/// A simulator that doesn't simulate anything,
+/// it, instead, collect what input has been given.
+/// Useful as a mock simulator for testing.
+///
+/// Implemented simulatables:
+/// - `SetTo   <common_inputs::Key          , bool>`
+/// - `SetTo   <common_inputs::MouseButton  , bool>`
+/// - `SetTo   <common_inputs::Char         , bool>`
+/// - `SetTo   <common_inputs::PhysicalKey  , bool>`
+/// - `SetTo   <common_inputs::RawKey       , bool>`
+/// - `SetTo   <common_inputs::MousePosition, (i32, i32)>`
+/// - `ChangeBy<common_inputs::MousePosition, (i32, i32)>`
+/// - `ChangeBy<common_inputs::MouseScroll  , (i32, i32)>`
+/// - `Execute <common_inputs::StrSimTuple>`
+///
+/// To log your own input, implement `Simulate` for this
+/// and call [`EventLog::push_other`].
+///
+/// `Sleep` and `SpinSleep` are not logged.
+/// The clock is real by default, use [`EventLog::with_clock`]
+/// with a [`VirtualClock`](crate::clock::VirtualClock) to not actually wait.
+/// Time of each event is logged in `times`,
+/// check them with [`assert_gap!`] and [`assert_time!`].
+///
+/// ```
+/// use kemuler::{prelude::*, event_log::EventLog};
+///
+/// let mut s = EventLog::new();
+/// Key::Shift.click()
+///     .then(Char('a').click().repeat(3))
+///     .run_with(&mut s);
+///
+/// assert_eq!(s.presses(Char('a')), 3);
+/// assert_eq!(s.position(Char('a').up()), Some(3));
+/// assert_eq!(s.filter_input(Key::Shift).count(), 2);
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+pub struct EventLog<C = RealClock> {
+    pub events: Vec<Event>,
+    pub times: Vec<Duration>,
+    pub clock: C,
+}
+
+impl EventLog {
+    pub fn new() -> EventLog {
+        EventLog::default()
+    }
+}
+
+impl<C> EventLog<C>
+where
+    C: Clock,
+{
+    pub fn with_clock(clock: C) -> EventLog<C> {
+        EventLog {
+            events: Vec::new(),
+            times: Vec::new(),
+            clock,
+        }
+    }
+
+    pub fn push<E: Into<Event>>(&mut self, event: E) {
+        self.events.push(event.into());
+        self.times.push(self.clock.now());
+    }
+
+    /// Log any event by its `Debug` representation.
+    pub fn push_other<T: fmt::Debug>(&mut self, t: &T) {
+        self.push(Event::Other(format!("{:?}", t)));
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.times.clear();
+    }
+}
+
+impl<C> EventLog<C> {
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Every logged event with the time it was logged.
+    pub fn iter(&self) -> impl Iterator<Item = (&Event, Duration)> + '_ {
+        self.events.iter().zip(self.times.iter().copied())
+    }
+
+    /// Every logged event that sets this button.
+    pub fn filter_input<I: Into<ButtonLike>>(&self, input: I) -> impl Iterator<Item = &Event> + '_ {
+        let input = input.into();
+        self.events
+            .iter()
+            .filter(move |e| matches!(e.button(), Some((button, _)) if button == input))
+    }
+
+    /// How many times this button was pressed.
+    pub fn presses<I: Into<ButtonLike>>(&self, input: I) -> usize {
+        let input = input.into();
+        self.events
+            .iter()
+            .filter(|e| e.button() == Some((input, true)))
+            .count()
+    }
+
+    /// Index of the first occurrence of this event.
+    pub fn position<E: Into<Event>>(&self, event: E) -> Option<usize> {
+        let event = event.into();
+        self.events.iter().position(|e| *e == event)
+    }
+}
+
+macro_rules! impl_logger {
+    ($( $(#[$attr:meta])* $ty:ty)*) => {
+        $(
+            $(#[$attr])*
+            impl<C: Clock> Simulate<$ty> for EventLog<C> {
+                fn simulate(&mut self, simulatable: $ty) {
+                    self.push(simulatable)
+                }
+            }
+
+            $(#[$attr])*
+            impl<C: Clock> TrySimulate<$ty> for EventLog<C> {
+                type Error = Infallible;
+
+                fn try_simulate(&mut self, simulatable: $ty) -> Result<(), Infallible> {
+                    self.push(simulatable);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_logger! {
+    SetTo<Key, bool>
+    SetTo<MouseButton, bool>
+    SetTo<Char, bool>
+    SetTo<PhysicalKey, bool>
+    SetTo<RawKey, bool>
+    SetTo<MousePosition, (i32, i32)>
+    ChangeBy<MousePosition, (i32, i32)>
+    ChangeBy<MouseScroll, (i32, i32)>
+}
+
+impl<'a, C: Clock> Simulate<Execute<StrSimTuple<'a>>> for EventLog<C> {
+    fn simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) {
+        self.push(simulatable)
+    }
+}
+
+impl<'a, C: Clock> TrySimulate<Execute<StrSimTuple<'a>>> for EventLog<C> {
+    type Error = Infallible;
+
+    fn try_simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) -> Result<(), Infallible> {
+        self.push(simulatable);
+        Ok(())
+    }
+}
+
+impl<C: Clock> Clock for EventLog<C> {
+    fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.clock.sleep(duration)
+    }
+
+    fn spin_sleep(&mut self, duration: Duration) {
+        self.clock.spin_sleep(duration)
+    }
+
+    fn sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        self.clock.sleep_or_cancel(duration, handle)
+    }
+
+    fn spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        self.clock.spin_sleep_or_cancel(duration, handle)
+    }
+}
+
+impl<C: Clock> TryClock for EventLog<C> {
+    type Error = Infallible;
+
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Infallible> {
+        self.clock.sleep(duration);
+        Ok(())
+    }
+
+    fn try_spin_sleep(&mut self, duration: Duration) -> Result<(), Infallible> {
+        self.clock.spin_sleep(duration);
+        Ok(())
+    }
+
+    fn try_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Infallible>> {
+        Ok(self.clock.sleep_or_cancel(duration, handle)?)
+    }
+
+    fn try_spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Infallible>> {
+        Ok(self.clock.spin_sleep_or_cancel(duration, handle)?)
+    }
+}
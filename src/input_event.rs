@@ -1,6 +1,9 @@
 //! Module containing base input events.
 
-use crate::{simulatable::Simulatable, simulator::Simulate};
+use crate::{
+    simulatable::{Simulatable, SimulatableMut, StepError, TrySimulatable, TrySimulatableMut},
+    simulator::{Simulate, TrySimulate},
+};
 use std::fmt;
 
 /// Trait for an event that is the inverse version of self.
@@ -39,6 +42,35 @@ where
     }
 }
 
+impl<I, V, Smltr> TrySimulatable<Smltr, Smltr::Error> for SetTo<I, V>
+where
+    Smltr: TrySimulate<Self>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_simulate(self).map_err(StepError::new)
+    }
+}
+
+impl<I, V, Smltr> SimulatableMut<Smltr> for SetTo<I, V>
+where
+    Self: Clone,
+    Smltr: Simulate<Self>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        simulator.simulate(self.clone())
+    }
+}
+
+impl<I, V, Smltr> TrySimulatableMut<Smltr, Smltr::Error> for SetTo<I, V>
+where
+    Self: Clone,
+    Smltr: TrySimulate<Self>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_simulate(self.clone()).map_err(StepError::new)
+    }
+}
+
 impl<I> Invert for SetTo<I, bool> {
     type Output = SetTo<I, bool>;
 
@@ -81,6 +113,35 @@ where
     }
 }
 
+impl<I, V, Smltr> TrySimulatable<Smltr, Smltr::Error> for ChangeBy<I, V>
+where
+    Smltr: TrySimulate<Self>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_simulate(self).map_err(StepError::new)
+    }
+}
+
+impl<I, V, Smltr> SimulatableMut<Smltr> for ChangeBy<I, V>
+where
+    Self: Clone,
+    Smltr: Simulate<Self>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        simulator.simulate(self.clone())
+    }
+}
+
+impl<I, V, Smltr> TrySimulatableMut<Smltr, Smltr::Error> for ChangeBy<I, V>
+where
+    Self: Clone,
+    Smltr: TrySimulate<Self>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_simulate(self.clone()).map_err(StepError::new)
+    }
+}
+
 impl<I, V> fmt::Display for ChangeBy<I, V>
 where
     I: fmt::Display,
@@ -111,6 +172,35 @@ where
     }
 }
 
+impl<I, Smltr> TrySimulatable<Smltr, Smltr::Error> for Execute<I>
+where
+    Smltr: TrySimulate<Self>,
+{
+    fn try_run_with(self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_simulate(self).map_err(StepError::new)
+    }
+}
+
+impl<I, Smltr> SimulatableMut<Smltr> for Execute<I>
+where
+    Self: Clone,
+    Smltr: Simulate<Self>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        simulator.simulate(self.clone())
+    }
+}
+
+impl<I, Smltr> TrySimulatableMut<Smltr, Smltr::Error> for Execute<I>
+where
+    Self: Clone,
+    Smltr: TrySimulate<Self>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<Smltr::Error>> {
+        simulator.try_simulate(self.clone()).map_err(StepError::new)
+    }
+}
+
 impl<I> fmt::Display for Execute<I>
 where
     I: fmt::Display,
@@ -0,0 +1,367 @@
+//! Keyboard layouts, which physical key and modifiers type a character.
+//!
+//! ```
+//! use kemuler::layout::{self, Modifiers, PhysicalKey};
+//!
+//! assert_eq!(layout::DE.keystroke_for('€'), Some((PhysicalKey::KeyE, Modifiers::ALT_GR)));
+//! assert_eq!(layout::FR.keystroke_for('A'), Some((PhysicalKey::KeyQ, Modifiers::SHIFT)));
+//! assert_eq!(layout::US.keystroke_for('€'), None);
+//! assert_eq!(layout::DE.char_for(PhysicalKey::KeyY, Modifiers::NONE), Some('z'));
+//!
+//! // accented characters may need a dead key first
+//! assert_eq!(
+//!     layout::DE.keystrokes_for('ê'),
+//!     Some(vec![
+//!         (PhysicalKey::Backquote, Modifiers::NONE),
+//!         (PhysicalKey::KeyE, Modifiers::NONE),
+//!     ]),
+//! );
+//! ```
+
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+pub use crate::common_inputs::PhysicalKey;
+
+/// The rows of typing keys and how far each row is shifted right,
+/// in quarters of a key.
+#[rustfmt::skip]
+const ROWS: [(i32, &[PhysicalKey]); 4] = {
+    use PhysicalKey::*;
+    [
+        (0, &[Backquote, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9, Digit0, Minus, Equal]),
+        (6, &[KeyQ, KeyW, KeyE, KeyR, KeyT, KeyY, KeyU, KeyI, KeyO, KeyP, BracketLeft, BracketRight, Backslash]),
+        (7, &[KeyA, KeyS, KeyD, KeyF, KeyG, KeyH, KeyJ, KeyK, KeyL, Semicolon, Quote]),
+        (5, &[IntlBackslash, KeyZ, KeyX, KeyC, KeyV, KeyB, KeyN, KeyM, Comma, Period, Slash]),
+    ]
+};
+
+impl PhysicalKey {
+    /// The row and horizontal position of the key in quarters of a key.
+    fn position(self) -> Option<(i32, i32)> {
+        ROWS.iter().zip(0..).find_map(|((shift, keys), row)| {
+            let column = keys.iter().position(|k| *k == self)?;
+            Some((row, shift + 4 * column as i32))
+        })
+    }
+
+    /// The keys next to this one on a staggered keyboard,
+    /// none for Space, Enter and Tab.
+    ///
+    /// ```
+    /// use kemuler::layout::PhysicalKey::*;
+    ///
+    /// assert_eq!(KeyS.neighbors(), [KeyW, KeyE, KeyA, KeyD, KeyZ, KeyX]);
+    /// ```
+    pub fn neighbors(self) -> Vec<PhysicalKey> {
+        let (row, x) = match self.position() {
+            Some(position) => position,
+            None => return Vec::new(),
+        };
+        ROWS.iter()
+            .zip(0..)
+            .filter(|(_, r)| (row - r).abs() <= 1)
+            .flat_map(|((shift, keys), r)| {
+                keys.iter().zip(0..).filter_map(move |(key, column)| {
+                    let dx = (shift + 4 * column - x).abs();
+                    let next = if r == row { dx == 4 } else { dx < 4 };
+                    next.then(|| *key)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Modifiers held while pressing a key to type a character.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    /// The right Alt key on most non-US layouts,
+    /// held as [`Key::RightAlt`](crate::common_inputs::Key::RightAlt) when typing.
+    pub alt_gr: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        alt_gr: false,
+    };
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        alt_gr: false,
+    };
+    pub const ALT_GR: Modifiers = Modifiers {
+        shift: false,
+        alt_gr: true,
+    };
+    pub const SHIFT_ALT_GR: Modifiers = Modifiers {
+        shift: true,
+        alt_gr: true,
+    };
+
+    /// Index of the character typed with these modifiers in a layout table.
+    fn level(self) -> usize {
+        self.shift as usize + self.alt_gr as usize * 2
+    }
+
+    fn from_level(level: usize) -> Modifiers {
+        Modifiers {
+            shift: level & 1 != 0,
+            alt_gr: level & 2 != 0,
+        }
+    }
+}
+
+/// Accents of dead keys, the letters they go on and the accented letters.
+const ACCENTS: &[(char, &str, &str)] = &[
+    ('´', "aeiouyAEIOUY", "áéíóúýÁÉÍÓÚÝ"),
+    ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+    ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+    ('¨', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+    ('~', "anoANO", "ãñõÃÑÕ"),
+    ('¸', "cC", "çÇ"),
+];
+
+/// The accent and letter of an accented letter, like `'´'` and `'e'` for `'é'`.
+pub(crate) fn decompose(c: char) -> Option<(char, char)> {
+    ACCENTS.iter().find_map(|(accent, letters, accented)| {
+        accented
+            .chars()
+            .position(|a| a == c)
+            .and_then(|i| letters.chars().nth(i))
+            .map(|letter| (*accent, letter))
+    })
+}
+
+/// A keyboard layout.
+///
+/// Space, Enter and Tab type `' '`, `'\n'` and `'\t'` on every layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Layout {
+    name: &'static str,
+    /// The characters typed by a key with no modifier, Shift, AltGr and Shift+AltGr.
+    /// `'\0'` means nothing is typed, either no character or a dead key.
+    keys: &'static [(PhysicalKey, &'static str)],
+    /// The accents of dead keys, in the same order as `keys`.
+    dead_keys: &'static [(PhysicalKey, &'static str)],
+}
+
+const COMMON_KEYS: &[(PhysicalKey, &str)] = &[
+    (PhysicalKey::Space, " "),
+    (PhysicalKey::Enter, "\n"),
+    (PhysicalKey::Tab, "\t"),
+];
+
+impl Layout {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &'static (PhysicalKey, &'static str)> {
+        self.keys.iter().chain(COMMON_KEYS)
+    }
+
+    /// The key and modifiers that type the character,
+    /// the one with the least modifiers if there are many.
+    pub fn keystroke_for(&self, c: char) -> Option<(PhysicalKey, Modifiers)> {
+        if c == '\0' {
+            return None;
+        }
+        (0..4).find_map(|level| {
+            self.keys()
+                .find(|(_, chars)| chars.chars().nth(level) == Some(c))
+                .map(|(key, _)| (*key, Modifiers::from_level(level)))
+        })
+    }
+
+    /// The dead key and modifiers that put the accent on the next letter.
+    pub fn dead_key_for(&self, accent: char) -> Option<(PhysicalKey, Modifiers)> {
+        if accent == '\0' {
+            return None;
+        }
+        (0..4).find_map(|level| {
+            self.dead_keys
+                .iter()
+                .find(|(_, accents)| accents.chars().nth(level) == Some(accent))
+                .map(|(key, _)| (*key, Modifiers::from_level(level)))
+        })
+    }
+
+    /// The keystrokes that type the character, in order.
+    ///
+    /// This is the keystroke from [`Layout::keystroke_for`],
+    /// or a dead key then the letter for accented letters.
+    /// An accent of a dead key is typed with the dead key then Space.
+    pub fn keystrokes_for(&self, c: char) -> Option<Vec<(PhysicalKey, Modifiers)>> {
+        if let Some(keystroke) = self.keystroke_for(c) {
+            return Some(vec![keystroke]);
+        }
+        let (dead_key, next) = match decompose(c) {
+            Some((accent, letter)) => (self.dead_key_for(accent)?, letter),
+            None => (self.dead_key_for(c)?, ' '),
+        };
+        Some(vec![dead_key, self.keystroke_for(next)?])
+    }
+
+    /// The character the key types with the modifiers.
+    pub fn char_for(&self, key: PhysicalKey, modifiers: Modifiers) -> Option<char> {
+        self.keys()
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, chars)| chars.chars().nth(modifiers.level()))
+            .filter(|c| *c != '\0')
+    }
+
+    /// The accent the dead key puts on the next letter with the modifiers.
+    pub fn accent_for(&self, key: PhysicalKey, modifiers: Modifiers) -> Option<char> {
+        self.dead_keys
+            .iter()
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, accents)| accents.chars().nth(modifiers.level()))
+            .filter(|c| *c != '\0')
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Every layout in this module.
+pub const ALL: &[Layout] = &[US, UK, DE, FR, DVORAK];
+
+/// US QWERTY.
+#[rustfmt::skip]
+pub const US: Layout = Layout {
+    name: "US",
+    keys: {
+        use PhysicalKey::*;
+        &[
+            (Backquote, "`~"),
+            (Digit1, "1!"), (Digit2, "2@"), (Digit3, "3#"), (Digit4, "4$"), (Digit5, "5%"),
+            (Digit6, "6^"), (Digit7, "7&"), (Digit8, "8*"), (Digit9, "9("), (Digit0, "0)"),
+            (Minus, "-_"), (Equal, "=+"),
+            (KeyQ, "qQ"), (KeyW, "wW"), (KeyE, "eE"), (KeyR, "rR"), (KeyT, "tT"),
+            (KeyY, "yY"), (KeyU, "uU"), (KeyI, "iI"), (KeyO, "oO"), (KeyP, "pP"),
+            (BracketLeft, "[{"), (BracketRight, "]}"), (Backslash, "\\|"),
+            (KeyA, "aA"), (KeyS, "sS"), (KeyD, "dD"), (KeyF, "fF"), (KeyG, "gG"),
+            (KeyH, "hH"), (KeyJ, "jJ"), (KeyK, "kK"), (KeyL, "lL"),
+            (Semicolon, ";:"), (Quote, "'\""),
+            (KeyZ, "zZ"), (KeyX, "xX"), (KeyC, "cC"), (KeyV, "vV"), (KeyB, "bB"),
+            (KeyN, "nN"), (KeyM, "mM"),
+            (Comma, ",<"), (Period, ".>"), (Slash, "/?"),
+        ]
+    },
+    dead_keys: &[],
+};
+
+/// UK QWERTY.
+#[rustfmt::skip]
+pub const UK: Layout = Layout {
+    name: "UK",
+    keys: {
+        use PhysicalKey::*;
+        &[
+            (Backquote, "`¬¦"),
+            (Digit1, "1!"), (Digit2, "2\""), (Digit3, "3£"), (Digit4, "4$€"), (Digit5, "5%"),
+            (Digit6, "6^"), (Digit7, "7&"), (Digit8, "8*"), (Digit9, "9("), (Digit0, "0)"),
+            (Minus, "-_"), (Equal, "=+"),
+            (KeyQ, "qQ"), (KeyW, "wW"), (KeyE, "eEéÉ"), (KeyR, "rR"), (KeyT, "tT"),
+            (KeyY, "yY"), (KeyU, "uUúÚ"), (KeyI, "iIíÍ"), (KeyO, "oOóÓ"), (KeyP, "pP"),
+            (BracketLeft, "[{"), (BracketRight, "]}"),
+            (KeyA, "aAáÁ"), (KeyS, "sS"), (KeyD, "dD"), (KeyF, "fF"), (KeyG, "gG"),
+            (KeyH, "hH"), (KeyJ, "jJ"), (KeyK, "kK"), (KeyL, "lL"),
+            (Semicolon, ";:"), (Quote, "'@"), (Backslash, "#~"),
+            (IntlBackslash, "\\|"),
+            (KeyZ, "zZ"), (KeyX, "xX"), (KeyC, "cC"), (KeyV, "vV"), (KeyB, "bB"),
+            (KeyN, "nN"), (KeyM, "mM"),
+            (Comma, ",<"), (Period, ".>"), (Slash, "/?"),
+        ]
+    },
+    dead_keys: &[],
+};
+
+/// German QWERTZ.
+#[rustfmt::skip]
+pub const DE: Layout = Layout {
+    name: "DE",
+    keys: {
+        use PhysicalKey::*;
+        &[
+            (Backquote, "\0°"),
+            (Digit1, "1!"), (Digit2, "2\"²"), (Digit3, "3§³"), (Digit4, "4$"), (Digit5, "5%"),
+            (Digit6, "6&"), (Digit7, "7/{"), (Digit8, "8(["), (Digit9, "9)]"), (Digit0, "0=}"),
+            (Minus, "ß?\\"), (Equal, "\0\0"),
+            (KeyQ, "qQ@"), (KeyW, "wW"), (KeyE, "eE€"), (KeyR, "rR"), (KeyT, "tT"),
+            (KeyY, "zZ"), (KeyU, "uU"), (KeyI, "iI"), (KeyO, "oO"), (KeyP, "pP"),
+            (BracketLeft, "üÜ"), (BracketRight, "+*~"),
+            (KeyA, "aA"), (KeyS, "sS"), (KeyD, "dD"), (KeyF, "fF"), (KeyG, "gG"),
+            (KeyH, "hH"), (KeyJ, "jJ"), (KeyK, "kK"), (KeyL, "lL"),
+            (Semicolon, "öÖ"), (Quote, "äÄ"), (Backslash, "#'"),
+            (IntlBackslash, "<>|"),
+            (KeyZ, "yY"), (KeyX, "xX"), (KeyC, "cC"), (KeyV, "vV"), (KeyB, "bB"),
+            (KeyN, "nN"), (KeyM, "mMµ"),
+            (Comma, ",;"), (Period, ".:"), (Slash, "-_"),
+        ]
+    },
+    dead_keys: {
+        use PhysicalKey::*;
+        &[(Backquote, "^"), (Equal, "´`")]
+    },
+};
+
+/// French AZERTY.
+#[rustfmt::skip]
+pub const FR: Layout = Layout {
+    name: "FR",
+    keys: {
+        use PhysicalKey::*;
+        &[
+            (Backquote, "²"),
+            (Digit1, "&1"), (Digit2, "é2"), (Digit3, "\"3#"), (Digit4, "'4{"), (Digit5, "(5["),
+            (Digit6, "-6|"), (Digit7, "è7"), (Digit8, "_8\\"), (Digit9, "ç9^"), (Digit0, "à0@"),
+            (Minus, ")°]"), (Equal, "=+}"),
+            (KeyQ, "aA"), (KeyW, "zZ"), (KeyE, "eE€"), (KeyR, "rR"), (KeyT, "tT"),
+            (KeyY, "yY"), (KeyU, "uU"), (KeyI, "iI"), (KeyO, "oO"), (KeyP, "pP"),
+            (BracketLeft, "\0\0"), (BracketRight, "$£¤"),
+            (KeyA, "qQ"), (KeyS, "sS"), (KeyD, "dD"), (KeyF, "fF"), (KeyG, "gG"),
+            (KeyH, "hH"), (KeyJ, "jJ"), (KeyK, "kK"), (KeyL, "lL"),
+            (Semicolon, "mM"), (Quote, "ù%"), (Backslash, "*µ"),
+            (IntlBackslash, "<>"),
+            (KeyZ, "wW"), (KeyX, "xX"), (KeyC, "cC"), (KeyV, "vV"), (KeyB, "bB"),
+            (KeyN, "nN"), (KeyM, ",?"),
+            (Comma, ";."), (Period, ":/"), (Slash, "!§"),
+        ]
+    },
+    dead_keys: {
+        use PhysicalKey::*;
+        &[(BracketLeft, "^¨"), (Digit2, "\0\0~"), (Digit7, "\0\0`")]
+    },
+};
+
+/// US Dvorak.
+#[rustfmt::skip]
+pub const DVORAK: Layout = Layout {
+    name: "Dvorak",
+    keys: {
+        use PhysicalKey::*;
+        &[
+            (Backquote, "`~"),
+            (Digit1, "1!"), (Digit2, "2@"), (Digit3, "3#"), (Digit4, "4$"), (Digit5, "5%"),
+            (Digit6, "6^"), (Digit7, "7&"), (Digit8, "8*"), (Digit9, "9("), (Digit0, "0)"),
+            (Minus, "[{"), (Equal, "]}"),
+            (KeyQ, "'\""), (KeyW, ",<"), (KeyE, ".>"), (KeyR, "pP"), (KeyT, "yY"),
+            (KeyY, "fF"), (KeyU, "gG"), (KeyI, "cC"), (KeyO, "rR"), (KeyP, "lL"),
+            (BracketLeft, "/?"), (BracketRight, "=+"), (Backslash, "\\|"),
+            (KeyA, "aA"), (KeyS, "oO"), (KeyD, "eE"), (KeyF, "uU"), (KeyG, "iI"),
+            (KeyH, "dD"), (KeyJ, "hH"), (KeyK, "tT"), (KeyL, "nN"),
+            (Semicolon, "sS"), (Quote, "-_"),
+            (KeyZ, ";:"), (KeyX, "qQ"), (KeyC, "jJ"), (KeyV, "kK"), (KeyB, "xX"),
+            (KeyN, "bB"), (KeyM, "mM"),
+            (Comma, "wW"), (Period, "vV"), (Slash, "zZ"),
+        ]
+    },
+    dead_keys: &[],
+};
@@ -0,0 +1,105 @@
+use crate::event_log::EventLog as S;
+use crate::prelude::*;
+use crate::typing::{TypeText, UnicodeInput};
+
+use super::*;
+
+#[test]
+fn layout_round_trip() {
+    for layout in ALL {
+        for (key, chars) in layout.keys() {
+            for (level, c) in chars.chars().enumerate() {
+                if c == '\0' {
+                    continue;
+                }
+                let modifiers = Modifiers::from_level(level);
+                assert_eq!(layout.char_for(*key, modifiers), Some(c), "{layout} {key}");
+                let (key, modifiers) = layout.keystroke_for(c).unwrap();
+                assert_eq!(layout.char_for(key, modifiers), Some(c), "{layout} {c:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn layout_letters_and_digits() {
+    for layout in ALL {
+        for c in ('a'..='z').chain('A'..='Z').chain('0'..='9') {
+            assert!(layout.keystroke_for(c).is_some(), "{layout} {c:?}");
+        }
+    }
+}
+
+#[test]
+fn layout_type_text_alt_gr() {
+    let mut s = S::new();
+    TypeText::new("@Z").layout(DE).run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        Key::RightAlt.down(),
+        Char('q').down(),
+        Char('q').up(),
+        Key::RightAlt.up(),
+        Key::Shift.down(),
+        Char('z').down(),
+        Char('z').up(),
+        Key::Shift.up(),
+    );
+    assert_eq!(s.events.len(), 8);
+}
+
+#[test]
+fn layout_type_text_dead_keys() {
+    let mut s = S::new();
+    TypeText::new("é").layout(DE).run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        PhysicalKey::Equal.down(),
+        PhysicalKey::Equal.up(),
+        Char('e').down(),
+        Char('e').up(),
+    );
+    assert_eq!(s.events.len(), 4);
+
+    let mut s = S::new();
+    TypeText::new("ñ")
+        .layout(US)
+        .compose_key(Key::Alt)
+        .run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        Key::Alt.down(),
+        Key::Alt.up(),
+        Key::Shift.down(),
+        Char('`').down(),
+        Char('`').up(),
+        Key::Shift.up(),
+        Char('n').down(),
+        Char('n').up(),
+    );
+
+    let mut s = S::new();
+    TypeText::new("ñ")
+        .layout(DE)
+        .unicode_input(UnicodeInput::CtrlShiftU)
+        .run_with(&mut s);
+    assert_events!(
+        s,
+        0,
+        Key::Control.down(),
+        Key::Shift.down(),
+        Char('u').down(),
+        Char('u').up(),
+        Key::Shift.up(),
+        Key::Control.up(),
+        Char('f').down(),
+        Char('f').up(),
+        Char('1').down(),
+        Char('1').up(),
+        Key::Space.down(),
+        Key::Space.up(),
+    );
+}
@@ -4,7 +4,7 @@
 //! ## The basic
 //!
 //! ```
-//! # use kemuler::string_event_logger::StringEventLogger as Simulator;
+//! # use kemuler::event_log::EventLog as Simulator;
 //! # use kemuler::assert_events;
 //! use kemuler::prelude::*;
 //!
@@ -28,7 +28,7 @@
 //!
 //! `.then` method chains simulatable together to run one by one.
 //! ```
-//! # use kemuler::string_event_logger::StringEventLogger as Simulator;
+//! # use kemuler::event_log::EventLog as Simulator;
 //! # use kemuler::assert_events;
 //! use kemuler::prelude::*;
 //!
@@ -54,7 +54,7 @@
 //! Chain simulatables one by one starting from `.0`.
 //! ```
 //! # use kemuler::{
-//! #     string_event_logger::StringEventLogger as Simulator,
+//! #     event_log::EventLog as Simulator,
 //! #     assert_events, prelude::*
 //! # };
 //! # let mut s = Simulator::new();
@@ -85,7 +85,7 @@
 //! This supports every type that implements `IntoIterator` and item is `Simulatable`.
 //! ```
 //! # use kemuler::{
-//! #     string_event_logger::StringEventLogger as Simulator,
+//! #     event_log::EventLog as Simulator,
 //! #     assert_events, prelude::*
 //! # };
 //! # let mut s = Simulator::new();
@@ -111,7 +111,7 @@
 //! Other useful combinators!
 //! ```
 //! # use kemuler::{
-//! #     string_event_logger::StringEventLogger as Simulator,
+//! #     event_log::EventLog as Simulator,
 //! #     assert_events, prelude::*
 //! # };
 //! # let mut s = Simulator::new();
@@ -162,24 +162,30 @@ macro_rules! doc_events {
             "#     s, 0,\n",
             $(doc_event!($events), "\n",)+
             "# );\n",
-            "# assert_eq!(s.data.len(), ",
+            "# assert_eq!(s.events.len(), ",
             $len,
             ");",
         )
     };
 }
+pub mod any_event;
+pub mod auto_release;
+pub mod cancel;
+pub mod clock;
 pub mod combinator;
+pub mod evdev;
+#[macro_use]
+pub mod event_log;
 pub mod input_event;
+pub mod layout;
+pub mod send_keys;
+pub mod shortcut;
 pub mod simulatable;
 pub mod simulator;
+pub mod state_tracker;
+pub mod typing;
 
 pub mod common_inputs;
-// this is pub because it only exist in test so no user can see it
-// (unless feature = "test")
-// (reminder for my dumbass)
-#[cfg(any(test, doctest, feature = "test"))]
-#[macro_use]
-pub mod string_event_logger;
 pub mod utils;
 
 pub mod prelude {
@@ -191,5 +197,5 @@ pub mod prelude {
     pub use combinator::SpinSleep;
     pub use combinator::{Combine, Sleep};
     pub use common_inputs::*;
-    pub use simulatable::Simulatable;
+    pub use simulatable::{Simulatable, TrySimulatable};
 }
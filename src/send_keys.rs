@@ -0,0 +1,293 @@
+//! Type text with a brace notation for keys, like AutoHotkey's `Send`.
+//!
+//! - Characters are clicked as [`Char`], except a new line clicks [`Key::Enter`]
+//!   and a tab clicks [`Key::Tab`].
+//! - `{Name}` clicks an input, the name is read like in a [`Shortcut`](crate::shortcut),
+//!   such as `{Enter}`, `{Ctrl}`, `{F5}`, `{MouseLeft}` or `{a}`.
+//! - `{Name down}` and `{Name up}` only press or release the input.
+//! - `{Name 3}` clicks the input 3 times.
+//! - `{{}` and `{}}` type a literal `{` and `}`.
+//!
+//! ```
+//! # use kemuler::assert_events;
+//! use kemuler::{prelude::*, send_keys::SendKeys, event_log::EventLog};
+//!
+//! let keys: SendKeys = "Hi{Enter}{Ctrl down}a{Ctrl up}{Tab 2}{{}".parse().unwrap();
+//!
+//! let mut s = EventLog::new();
+//! keys.run_with(&mut s);
+//! assert_events!(
+//!     s, 0,
+//!     Char('H').down(),
+//!     Char('H').up(),
+//!     Char('i').down(),
+//!     Char('i').up(),
+//!     Key::Enter.down(),
+//!     Key::Enter.up(),
+//!     Key::Control.down(),
+//!     Char('a').down(),
+//!     Char('a').up(),
+//!     Key::Control.up(),
+//!     Key::Tab.down(),
+//!     Key::Tab.up(),
+//!     Key::Tab.down(),
+//!     Key::Tab.up(),
+//!     Char('{').down(),
+//!     Char('{').up(),
+//! );
+//! ```
+//!
+//! Inputs pressed with `{Name down}` stay pressed until `{Name up}`,
+//! wrap the simulator with [`AutoRelease`](crate::auto_release::AutoRelease)
+//! if the script may not release them.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::{
+    common_inputs::{Char, Key, MouseButton},
+    input_event::SetTo,
+    shortcut::{parse_input, set_button, try_set_button, ShortcutInput},
+    simulatable::{Simulatable, SimulatableMut, StepError, TrySimulatable, TrySimulatableMut},
+    simulator::{Simulate, TrySimulate},
+};
+
+#[cfg(test)]
+mod test;
+
+/// A key of [`SendKeys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SendKey {
+    /// Click the input a number of times.
+    Click(ShortcutInput, usize),
+    /// Only press the input.
+    Down(ShortcutInput),
+    /// Only release the input.
+    Up(ShortcutInput),
+}
+
+/// Keys parsed from the brace notation, see the [module](self) documentation.
+///
+/// Simulated one key after another,
+/// the simulator must support `SetTo<I, bool>` of `Key`, `MouseButton` and `Char`.
+/// When fallibly simulated, a failure is reported at the index of the key,
+/// and for clicks then at the index of the click and 0 for its press or 1 for its release.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SendKeys {
+    pub keys: Vec<SendKey>,
+}
+
+impl SendKeys {
+    pub fn new(keys: Vec<SendKey>) -> SendKeys {
+        SendKeys { keys }
+    }
+
+    /// Same as `str::parse`.
+    pub fn parse(s: &str) -> Result<SendKeys, ParseSendKeysError> {
+        s.parse()
+    }
+}
+
+/// Parse what's inside the braces starting at `at`.
+fn parse_braced(at: usize, content: &str) -> Result<SendKey, ParseSendKeysError> {
+    // the name ends at whitespace, its first character can be anything like in `{} 3}`
+    let first_len = match content.chars().next() {
+        Some(c) => c.len_utf8(),
+        None => return Err(ParseSendKeysError::EmptyBraces { at }),
+    };
+    let (name, argument) = match content[first_len..].find(char::is_whitespace) {
+        Some(i) => content.split_at(first_len + i),
+        None => (content, ""),
+    };
+    let argument = argument.trim();
+    let input = parse_input(name).ok_or_else(|| ParseSendKeysError::UnknownInput {
+        at,
+        name: name.to_owned(),
+    })?;
+    if argument.is_empty() {
+        Ok(SendKey::Click(input, 1))
+    } else if argument.eq_ignore_ascii_case("down") {
+        Ok(SendKey::Down(input))
+    } else if argument.eq_ignore_ascii_case("up") {
+        Ok(SendKey::Up(input))
+    } else {
+        // only digits, `usize::from_str` also accepts a leading `+`
+        match argument.parse::<usize>() {
+            Ok(times) if argument.bytes().all(|b| b.is_ascii_digit()) => {
+                Ok(SendKey::Click(input, times))
+            }
+            _ => Err(ParseSendKeysError::InvalidArgument {
+                at,
+                argument: argument.to_owned(),
+            }),
+        }
+    }
+}
+
+impl FromStr for SendKeys {
+    type Err = ParseSendKeysError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keys = Vec::new();
+        let mut rest = s;
+        while let Some(c) = rest.chars().next() {
+            let at = s.len() - rest.len();
+            rest = &rest[c.len_utf8()..];
+            match c {
+                '{' => {
+                    // `}` can only be the first character inside, as in `{}}` and `{} 3}`
+                    let end = match rest.strip_prefix('}') {
+                        Some(after) if after.starts_with(['}', ' ']) => {
+                            after.find('}').map(|i| i + 1)
+                        }
+                        Some(_) => return Err(ParseSendKeysError::EmptyBraces { at }),
+                        None => rest.find('}'),
+                    };
+                    let content = match end {
+                        Some(end) => &rest[..end],
+                        None => return Err(ParseSendKeysError::UnclosedBrace { at }),
+                    };
+                    // `{` can only be the first character inside, as in `{{}`
+                    if content.chars().skip(1).any(|c| c == '{') {
+                        return Err(ParseSendKeysError::UnclosedBrace { at });
+                    }
+                    keys.push(parse_braced(at, content)?);
+                    rest = &rest[content.len() + 1..];
+                }
+                '}' => return Err(ParseSendKeysError::UnmatchedBrace { at }),
+                '\n' => keys.push(SendKey::Click(Key::Enter.into(), 1)),
+                '\t' => keys.push(SendKey::Click(Key::Tab.into(), 1)),
+                c => keys.push(SendKey::Click(Char(c).into(), 1)),
+            }
+        }
+        Ok(SendKeys { keys })
+    }
+}
+
+/// An error from parsing [`SendKeys`],
+/// `at` is the byte index of the brace with the error.
+///
+/// ```
+/// use kemuler::send_keys::{SendKeys, ParseSendKeysError};
+///
+/// let err = SendKeys::parse("Hi{Entr}").unwrap_err();
+/// assert_eq!(err, ParseSendKeysError::UnknownInput { at: 2, name: "Entr".to_owned() });
+/// assert_eq!(
+///     err.to_string(),
+///     "unknown input `Entr` in braces at 2, \
+///      expected a key name like `Enter` or `F1`, or a single character",
+/// );
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParseSendKeysError {
+    /// A `{` is never closed.
+    UnclosedBrace { at: usize },
+    /// A `}` is not opened.
+    UnmatchedBrace { at: usize },
+    /// There is nothing in the braces.
+    EmptyBraces { at: usize },
+    /// An input name is not known.
+    UnknownInput { at: usize, name: String },
+    /// The argument after the input name is not `down`, `up` or a count.
+    InvalidArgument { at: usize, argument: String },
+}
+
+impl fmt::Display for ParseSendKeysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseSendKeysError::UnclosedBrace { at } => {
+                write!(f, "unclosed brace at {at}, use `{{{{}}` for a literal `{{`")
+            }
+            ParseSendKeysError::UnmatchedBrace { at } => {
+                write!(
+                    f,
+                    "unmatched brace at {at}, use `{{}}}}` for a literal `}}`"
+                )
+            }
+            ParseSendKeysError::EmptyBraces { at } => write!(f, "empty braces at {at}"),
+            ParseSendKeysError::UnknownInput { at, name } => write!(
+                f,
+                "unknown input `{name}` in braces at {at}, \
+                 expected a key name like `Enter` or `F1`, or a single character"
+            ),
+            ParseSendKeysError::InvalidArgument { at, argument } => write!(
+                f,
+                "invalid argument `{argument}` in braces at {at}, \
+                 expected `down`, `up` or a count"
+            ),
+        }
+    }
+}
+
+impl Error for ParseSendKeysError {}
+
+impl<Smltr> Simulatable<Smltr> for SendKeys
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>,
+{
+    fn run_with(mut self, simulator: &mut Smltr) {
+        self.run_mut(simulator)
+    }
+}
+
+impl<Smltr, E> TrySimulatable<Smltr, E> for SendKeys
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.try_run_mut(simulator)
+    }
+}
+
+impl<Smltr> SimulatableMut<Smltr> for SendKeys
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        for key in &self.keys {
+            match *key {
+                SendKey::Click(input, times) => {
+                    for _ in 0..times {
+                        set_button(simulator, SetTo::new(input, true));
+                        set_button(simulator, SetTo::new(input, false));
+                    }
+                }
+                SendKey::Down(input) => set_button(simulator, SetTo::new(input, true)),
+                SendKey::Up(input) => set_button(simulator, SetTo::new(input, false)),
+            }
+        }
+    }
+}
+
+impl<Smltr, E> TrySimulatableMut<Smltr, E> for SendKeys
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        for (i, key) in self.keys.iter().enumerate() {
+            match *key {
+                SendKey::Click(input, times) => {
+                    for j in 0..times {
+                        for (k, to) in [true, false].into_iter().enumerate() {
+                            try_set_button(simulator, SetTo::new(input, to))
+                                .map_err(|e| StepError::new(e).at(k).at(j).at(i))?;
+                        }
+                    }
+                }
+                SendKey::Down(input) => try_set_button(simulator, SetTo::new(input, true))
+                    .map_err(|e| StepError::new(e).at(i))?,
+                SendKey::Up(input) => try_set_button(simulator, SetTo::new(input, false))
+                    .map_err(|e| StepError::new(e).at(i))?,
+            }
+        }
+        Ok(())
+    }
+}
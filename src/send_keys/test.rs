@@ -0,0 +1,100 @@
+use crate::{
+    cancel::{CancelHandle, Cancellable},
+    event_log::EventLog as S,
+    prelude::*,
+};
+
+use super::*;
+
+#[test]
+fn send_keys_repeat() {
+    let keys = SendKeys::parse("{Tab 3}{Tab 0}").unwrap();
+    assert_eq!(
+        keys.keys,
+        [
+            SendKey::Click(Key::Tab.into(), 3),
+            SendKey::Click(Key::Tab.into(), 0),
+        ]
+    );
+
+    let mut s = S::new();
+    keys.run_with(&mut s);
+    assert_events!(s, 0, Key::Tab.down(), Key::Tab.up(),);
+    assert_eq!(s.events.len(), 6);
+
+    // a large count is stored as it is
+    let keys = SendKeys::parse(&format!("{{F1 {}}}", usize::MAX)).unwrap();
+    assert_eq!(keys.keys, [SendKey::Click(Key::F1.into(), usize::MAX)]);
+}
+
+#[test]
+fn send_keys_failure_step() {
+    let handle = CancelHandle::new();
+    let mut s = Cancellable::new(S::new(), handle.clone());
+    let mut keys = SendKeys::parse("a{Tab 3}").unwrap();
+    assert_eq!(keys.try_run_mut(&mut s), Ok(()));
+
+    handle.cancel();
+    let result = SendKeys::parse("{Tab 3}").unwrap().try_run_with(&mut s);
+    assert_eq!(result.unwrap_err().step, [0, 0, 0]);
+}
+
+#[test]
+fn send_keys_bad_count() {
+    assert_eq!(
+        SendKeys::parse("{Tab -1}"),
+        Err(ParseSendKeysError::InvalidArgument {
+            at: 0,
+            argument: "-1".to_owned(),
+        }),
+    );
+    assert_eq!(
+        SendKeys::parse("{a 99999999999999999999999}"),
+        Err(ParseSendKeysError::InvalidArgument {
+            at: 0,
+            argument: "99999999999999999999999".to_owned(),
+        }),
+    );
+    assert_eq!(
+        SendKeys::parse("{Tab +2}"),
+        Err(ParseSendKeysError::InvalidArgument {
+            at: 0,
+            argument: "+2".to_owned(),
+        }),
+    );
+    assert_eq!(
+        SendKeys::parse("{Tab 2x}"),
+        Err(ParseSendKeysError::InvalidArgument {
+            at: 0,
+            argument: "2x".to_owned(),
+        }),
+    );
+}
+
+#[test]
+fn send_keys_unclosed_brace() {
+    assert_eq!(
+        SendKeys::parse("ab{Tab"),
+        Err(ParseSendKeysError::UnclosedBrace { at: 2 }),
+    );
+    assert_eq!(
+        SendKeys::parse("{Tab{Enter}"),
+        Err(ParseSendKeysError::UnclosedBrace { at: 0 }),
+    );
+    assert_eq!(
+        SendKeys::parse("a}"),
+        Err(ParseSendKeysError::UnmatchedBrace { at: 1 }),
+    );
+}
+
+#[test]
+fn send_keys_empty_braces() {
+    assert_eq!(
+        SendKeys::parse("x{}"),
+        Err(ParseSendKeysError::EmptyBraces { at: 1 }),
+    );
+    assert_eq!(
+        SendKeys::parse("{}x"),
+        Err(ParseSendKeysError::EmptyBraces { at: 0 }),
+    );
+}
@@ -0,0 +1,449 @@
+//! Parse and format shortcut notation like `"Ctrl+Shift+T"`.
+//!
+//! A shortcut is a sequence of chords separated by whitespace,
+//! a chord is inputs joined with `+` that are pressed together.
+//! Input names are case insensitive and can be:
+//! - a [`Key`] name like `Control`, `F10` or `PageUp`, or an alias like `Ctrl`, `Esc`,
+//!   `Super` or `AltGr`
+//! - `KeyA` to `KeyZ` and `Digit0` to `Digit9` for the letter and digit [`Key`]s
+//! - `MouseLeft`, `MouseMiddle` or `MouseRight`
+//! - `Plus` for the `+` character
+//! - any other single character, which is a [`Char`]
+//!
+//! ```
+//! # use kemuler::assert_events;
+//! use kemuler::{prelude::*, shortcut::Shortcut, event_log::EventLog};
+//!
+//! let comment: Shortcut = "ctrl+k ctrl+c".parse().unwrap();
+//! assert_eq!(comment.to_string(), "Control+k Control+c");
+//! assert_eq!(Shortcut::parse(&comment.to_string()).unwrap(), comment);
+//!
+//! let mut s = EventLog::new();
+//! comment.run_with(&mut s);
+//! assert_events!(
+//!     s, 0,
+//!     Key::Control.down(),
+//!     Char('k').down(),
+//!     Char('k').up(),
+//!     Key::Control.up(),
+//!     Key::Control.down(),
+//!     Char('c').down(),
+//!     Char('c').up(),
+//!     Key::Control.up(),
+//! );
+//! ```
+
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::{
+    combinator::{run_chord, try_run_chord, SimTuple},
+    common_inputs::{ButtonLike, Char, Key, MouseButton},
+    input_event::SetTo,
+    simulatable::{Simulatable, SimulatableMut, StepError, TrySimulatable, TrySimulatableMut},
+    simulator::{Simulate, TrySimulate},
+};
+
+#[cfg(test)]
+mod test;
+
+/// Names of the inputs, the first name of a key is the formatted one.
+#[rustfmt::skip]
+const NAMES: &[(&[&str], ShortcutInput)] = &[
+    (&["Alt", "Option", "Opt"], ShortcutInput::Key(Key::Alt)),
+    (&["Shift"], ShortcutInput::Key(Key::Shift)),
+    (&["Control", "Ctrl"], ShortcutInput::Key(Key::Control)),
+    (&["Meta", "Super", "Win", "Command", "Cmd"], ShortcutInput::Key(Key::Meta)),
+    (&["LeftShift", "LShift"], ShortcutInput::Key(Key::LeftShift)),
+    (&["RightShift", "RShift"], ShortcutInput::Key(Key::RightShift)),
+    (&["LeftControl", "LCtrl"], ShortcutInput::Key(Key::LeftControl)),
+    (&["RightControl", "RCtrl"], ShortcutInput::Key(Key::RightControl)),
+    (&["LeftAlt", "LAlt"], ShortcutInput::Key(Key::LeftAlt)),
+    (&["RightAlt", "RAlt", "AltGr"], ShortcutInput::Key(Key::RightAlt)),
+    (&["LeftMeta", "LWin"], ShortcutInput::Key(Key::LeftMeta)),
+    (&["RightMeta", "RWin"], ShortcutInput::Key(Key::RightMeta)),
+    (&["KeyA"], ShortcutInput::Key(Key::A)),
+    (&["KeyB"], ShortcutInput::Key(Key::B)),
+    (&["KeyC"], ShortcutInput::Key(Key::C)),
+    (&["KeyD"], ShortcutInput::Key(Key::D)),
+    (&["KeyE"], ShortcutInput::Key(Key::E)),
+    (&["KeyF"], ShortcutInput::Key(Key::F)),
+    (&["KeyG"], ShortcutInput::Key(Key::G)),
+    (&["KeyH"], ShortcutInput::Key(Key::H)),
+    (&["KeyI"], ShortcutInput::Key(Key::I)),
+    (&["KeyJ"], ShortcutInput::Key(Key::J)),
+    (&["KeyK"], ShortcutInput::Key(Key::K)),
+    (&["KeyL"], ShortcutInput::Key(Key::L)),
+    (&["KeyM"], ShortcutInput::Key(Key::M)),
+    (&["KeyN"], ShortcutInput::Key(Key::N)),
+    (&["KeyO"], ShortcutInput::Key(Key::O)),
+    (&["KeyP"], ShortcutInput::Key(Key::P)),
+    (&["KeyQ"], ShortcutInput::Key(Key::Q)),
+    (&["KeyR"], ShortcutInput::Key(Key::R)),
+    (&["KeyS"], ShortcutInput::Key(Key::S)),
+    (&["KeyT"], ShortcutInput::Key(Key::T)),
+    (&["KeyU"], ShortcutInput::Key(Key::U)),
+    (&["KeyV"], ShortcutInput::Key(Key::V)),
+    (&["KeyW"], ShortcutInput::Key(Key::W)),
+    (&["KeyX"], ShortcutInput::Key(Key::X)),
+    (&["KeyY"], ShortcutInput::Key(Key::Y)),
+    (&["KeyZ"], ShortcutInput::Key(Key::Z)),
+    (&["Digit0"], ShortcutInput::Key(Key::Digit0)),
+    (&["Digit1"], ShortcutInput::Key(Key::Digit1)),
+    (&["Digit2"], ShortcutInput::Key(Key::Digit2)),
+    (&["Digit3"], ShortcutInput::Key(Key::Digit3)),
+    (&["Digit4"], ShortcutInput::Key(Key::Digit4)),
+    (&["Digit5"], ShortcutInput::Key(Key::Digit5)),
+    (&["Digit6"], ShortcutInput::Key(Key::Digit6)),
+    (&["Digit7"], ShortcutInput::Key(Key::Digit7)),
+    (&["Digit8"], ShortcutInput::Key(Key::Digit8)),
+    (&["Digit9"], ShortcutInput::Key(Key::Digit9)),
+    (&["F1"], ShortcutInput::Key(Key::F1)),
+    (&["F2"], ShortcutInput::Key(Key::F2)),
+    (&["F3"], ShortcutInput::Key(Key::F3)),
+    (&["F4"], ShortcutInput::Key(Key::F4)),
+    (&["F5"], ShortcutInput::Key(Key::F5)),
+    (&["F6"], ShortcutInput::Key(Key::F6)),
+    (&["F7"], ShortcutInput::Key(Key::F7)),
+    (&["F8"], ShortcutInput::Key(Key::F8)),
+    (&["F9"], ShortcutInput::Key(Key::F9)),
+    (&["F10"], ShortcutInput::Key(Key::F10)),
+    (&["F11"], ShortcutInput::Key(Key::F11)),
+    (&["F12"], ShortcutInput::Key(Key::F12)),
+    (&["F13"], ShortcutInput::Key(Key::F13)),
+    (&["F14"], ShortcutInput::Key(Key::F14)),
+    (&["F15"], ShortcutInput::Key(Key::F15)),
+    (&["F16"], ShortcutInput::Key(Key::F16)),
+    (&["F17"], ShortcutInput::Key(Key::F17)),
+    (&["F18"], ShortcutInput::Key(Key::F18)),
+    (&["F19"], ShortcutInput::Key(Key::F19)),
+    (&["F20"], ShortcutInput::Key(Key::F20)),
+    (&["F21"], ShortcutInput::Key(Key::F21)),
+    (&["F22"], ShortcutInput::Key(Key::F22)),
+    (&["F23"], ShortcutInput::Key(Key::F23)),
+    (&["F24"], ShortcutInput::Key(Key::F24)),
+    (&["CapsLock", "Caps"], ShortcutInput::Key(Key::CapsLock)),
+    (&["NumLock"], ShortcutInput::Key(Key::NumLock)),
+    (&["ScrollLock"], ShortcutInput::Key(Key::ScrollLock)),
+    (&["End"], ShortcutInput::Key(Key::End)),
+    (&["Home"], ShortcutInput::Key(Key::Home)),
+    (&["PageUp", "PgUp"], ShortcutInput::Key(Key::PageUp)),
+    (&["PageDown", "PgDn"], ShortcutInput::Key(Key::PageDown)),
+    (&["Escape", "Esc"], ShortcutInput::Key(Key::Escape)),
+    (&["Enter", "Return"], ShortcutInput::Key(Key::Enter)),
+    (&["Space"], ShortcutInput::Key(Key::Space)),
+    (&["Tab"], ShortcutInput::Key(Key::Tab)),
+    (&["Backspace"], ShortcutInput::Key(Key::Backspace)),
+    (&["Delete", "Del"], ShortcutInput::Key(Key::Delete)),
+    (&["Insert", "Ins"], ShortcutInput::Key(Key::Insert)),
+    (&["UpArrow", "Up"], ShortcutInput::Key(Key::UpArrow)),
+    (&["DownArrow", "Down"], ShortcutInput::Key(Key::DownArrow)),
+    (&["LeftArrow", "Left"], ShortcutInput::Key(Key::LeftArrow)),
+    (&["RightArrow", "Right"], ShortcutInput::Key(Key::RightArrow)),
+    (&["PrintScreen", "PrtSc"], ShortcutInput::Key(Key::PrintScreen)),
+    (&["Pause"], ShortcutInput::Key(Key::Pause)),
+    (&["Menu", "Apps"], ShortcutInput::Key(Key::Menu)),
+    (&["Numpad0", "Num0"], ShortcutInput::Key(Key::Numpad0)),
+    (&["Numpad1", "Num1"], ShortcutInput::Key(Key::Numpad1)),
+    (&["Numpad2", "Num2"], ShortcutInput::Key(Key::Numpad2)),
+    (&["Numpad3", "Num3"], ShortcutInput::Key(Key::Numpad3)),
+    (&["Numpad4", "Num4"], ShortcutInput::Key(Key::Numpad4)),
+    (&["Numpad5", "Num5"], ShortcutInput::Key(Key::Numpad5)),
+    (&["Numpad6", "Num6"], ShortcutInput::Key(Key::Numpad6)),
+    (&["Numpad7", "Num7"], ShortcutInput::Key(Key::Numpad7)),
+    (&["Numpad8", "Num8"], ShortcutInput::Key(Key::Numpad8)),
+    (&["Numpad9", "Num9"], ShortcutInput::Key(Key::Numpad9)),
+    (&["NumpadAdd"], ShortcutInput::Key(Key::NumpadAdd)),
+    (&["NumpadSubtract"], ShortcutInput::Key(Key::NumpadSubtract)),
+    (&["NumpadMultiply"], ShortcutInput::Key(Key::NumpadMultiply)),
+    (&["NumpadDivide"], ShortcutInput::Key(Key::NumpadDivide)),
+    (&["NumpadDecimal"], ShortcutInput::Key(Key::NumpadDecimal)),
+    (&["NumpadEnter"], ShortcutInput::Key(Key::NumpadEnter)),
+    (&["VolumeUp"], ShortcutInput::Key(Key::VolumeUp)),
+    (&["VolumeDown"], ShortcutInput::Key(Key::VolumeDown)),
+    (&["VolumeMute", "Mute"], ShortcutInput::Key(Key::VolumeMute)),
+    (&["MediaPlayPause", "PlayPause"], ShortcutInput::Key(Key::MediaPlayPause)),
+    (&["MediaStop"], ShortcutInput::Key(Key::MediaStop)),
+    (&["MediaNext"], ShortcutInput::Key(Key::MediaNext)),
+    (&["MediaPrevious", "MediaPrev"], ShortcutInput::Key(Key::MediaPrevious)),
+    (&["BrowserBack"], ShortcutInput::Key(Key::BrowserBack)),
+    (&["BrowserForward"], ShortcutInput::Key(Key::BrowserForward)),
+    (&["BrowserRefresh"], ShortcutInput::Key(Key::BrowserRefresh)),
+    (&["BrowserStop"], ShortcutInput::Key(Key::BrowserStop)),
+    (&["BrowserSearch"], ShortcutInput::Key(Key::BrowserSearch)),
+    (&["BrowserFavorites"], ShortcutInput::Key(Key::BrowserFavorites)),
+    (&["BrowserHome"], ShortcutInput::Key(Key::BrowserHome)),
+    (&["MouseLeft"], ShortcutInput::MouseButton(MouseButton::Left)),
+    (&["MouseMiddle"], ShortcutInput::MouseButton(MouseButton::Middle)),
+    (&["MouseRight"], ShortcutInput::MouseButton(MouseButton::Right)),
+    (&["Plus"], ShortcutInput::Char(Char('+'))),
+];
+
+/// Parse a single input name.
+pub(crate) fn parse_input(name: &str) -> Option<ShortcutInput> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(ShortcutInput::Char(Char(c)));
+    }
+    NAMES
+        .iter()
+        .find(|(names, _)| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+        .map(|(_, input)| *input)
+}
+
+/// An input of a [`Shortcut`], one the notation can name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShortcutInput {
+    Key(Key),
+    MouseButton(MouseButton),
+    Char(Char),
+}
+
+impl From<Key> for ShortcutInput {
+    fn from(value: Key) -> Self {
+        ShortcutInput::Key(value)
+    }
+}
+
+impl From<MouseButton> for ShortcutInput {
+    fn from(value: MouseButton) -> Self {
+        ShortcutInput::MouseButton(value)
+    }
+}
+
+impl From<Char> for ShortcutInput {
+    fn from(value: Char) -> Self {
+        ShortcutInput::Char(value)
+    }
+}
+
+impl From<ShortcutInput> for ButtonLike {
+    fn from(value: ShortcutInput) -> Self {
+        match value {
+            ShortcutInput::Key(key) => ButtonLike::Key(key),
+            ShortcutInput::MouseButton(button) => ButtonLike::MouseButton(button),
+            ShortcutInput::Char(c) => ButtonLike::Char(c),
+        }
+    }
+}
+
+/// A sequence of chords parsed from shortcut notation.
+///
+/// Each chord is pressed together like [`Chord`](crate::combinator::Chord),
+/// one chord after another.
+/// The simulator must support `SetTo<I, bool>` of `Key`, `MouseButton` and `Char`,
+/// the inputs a shortcut can name.
+/// Press a [`PhysicalKey`](crate::common_inputs::PhysicalKey) or
+/// [`RawKey`](crate::common_inputs::RawKey) with a `Chord` instead.
+///
+/// Formatting then parsing a parsed shortcut gives the same shortcut.
+/// Inputs the parser never makes, like `Char(' ')`, may not round-trip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub chords: Vec<Vec<ShortcutInput>>,
+}
+
+impl Shortcut {
+    pub fn new(chords: Vec<Vec<ShortcutInput>>) -> Shortcut {
+        Shortcut { chords }
+    }
+
+    /// Same as `str::parse`.
+    pub fn parse(s: &str) -> Result<Shortcut, ParseShortcutError> {
+        s.parse()
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chords = Vec::new();
+        for chord in s.split_whitespace() {
+            let mut inputs: Vec<ShortcutInput> = Vec::new();
+            for name in chord.split('+') {
+                if name.is_empty() {
+                    return Err(ParseShortcutError::MissingInput {
+                        chord: chord.to_owned(),
+                    });
+                }
+                let input = parse_input(name).ok_or_else(|| ParseShortcutError::UnknownInput {
+                    name: name.to_owned(),
+                    chord: chord.to_owned(),
+                })?;
+                if inputs.contains(&input) {
+                    return Err(ParseShortcutError::DuplicateInput {
+                        name: name.to_owned(),
+                        chord: chord.to_owned(),
+                    });
+                }
+                inputs.push(input);
+            }
+            chords.push(inputs);
+        }
+        if chords.is_empty() {
+            return Err(ParseShortcutError::Empty);
+        }
+        Ok(Shortcut { chords })
+    }
+}
+
+/// Write an input the way the parser reads it, named inputs by their first name.
+fn fmt_input(input: &ShortcutInput, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some((names, _)) = NAMES.iter().find(|(_, named)| named == input) {
+        return write!(f, "{}", names[0]);
+    }
+    match input {
+        ShortcutInput::Key(key) => write!(f, "{key}"),
+        ShortcutInput::MouseButton(button) => write!(f, "Mouse{button}"),
+        ShortcutInput::Char(c) => write!(f, "{c}"),
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, chord) in self.chords.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            for (j, input) in chord.iter().enumerate() {
+                if j != 0 {
+                    write!(f, "+")?;
+                }
+                fmt_input(input, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error from parsing a [`Shortcut`].
+///
+/// ```
+/// use kemuler::shortcut::{Shortcut, ParseShortcutError};
+///
+/// let err = Shortcut::parse("ctrl+shfit+t").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "unknown input `shfit` in `ctrl+shfit+t`, \
+///      expected a key name like `Ctrl` or `F1`, or a single character",
+/// );
+/// assert_eq!(
+///     Shortcut::parse("ctrl+").unwrap_err(),
+///     ParseShortcutError::MissingInput { chord: "ctrl+".to_owned() },
+/// );
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParseShortcutError {
+    /// There is no chord.
+    Empty,
+    /// A chord has nothing between two `+` or at either end.
+    MissingInput { chord: String },
+    /// An input name is not known.
+    UnknownInput { name: String, chord: String },
+    /// An input is in the same chord more than once.
+    DuplicateInput { name: String, chord: String },
+}
+
+impl fmt::Display for ParseShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseShortcutError::Empty => write!(f, "shortcut is empty"),
+            ParseShortcutError::MissingInput { chord } => {
+                write!(
+                    f,
+                    "missing input around `+` in `{chord}`, use `Plus` for the `+` character"
+                )
+            }
+            ParseShortcutError::UnknownInput { name, chord } => write!(
+                f,
+                "unknown input `{name}` in `{chord}`, \
+                 expected a key name like `Ctrl` or `F1`, or a single character"
+            ),
+            ParseShortcutError::DuplicateInput { name, chord } => {
+                write!(f, "input `{name}` is pressed more than once in `{chord}`")
+            }
+        }
+    }
+}
+
+impl Error for ParseShortcutError {}
+
+pub(crate) fn set_button<Smltr>(simulator: &mut Smltr, event: SetTo<ShortcutInput, bool>)
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>,
+{
+    match event.input {
+        ShortcutInput::Key(key) => key.set_to(event.to).run_with(simulator),
+        ShortcutInput::MouseButton(button) => button.set_to(event.to).run_with(simulator),
+        ShortcutInput::Char(c) => c.set_to(event.to).run_with(simulator),
+    }
+}
+
+pub(crate) fn try_set_button<Smltr, E>(
+    simulator: &mut Smltr,
+    event: SetTo<ShortcutInput, bool>,
+) -> Result<(), E>
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>,
+{
+    match event.input {
+        ShortcutInput::Key(key) => simulator.try_simulate(key.set_to(event.to)),
+        ShortcutInput::MouseButton(button) => simulator.try_simulate(button.set_to(event.to)),
+        ShortcutInput::Char(c) => simulator.try_simulate(c.set_to(event.to)),
+    }
+}
+
+impl<Smltr> Simulatable<Smltr> for Shortcut
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>,
+{
+    fn run_with(mut self, simulator: &mut Smltr) {
+        self.run_mut(simulator)
+    }
+}
+
+impl<Smltr, E> TrySimulatable<Smltr, E> for Shortcut
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.try_run_mut(simulator)
+    }
+}
+
+impl<Smltr> SimulatableMut<Smltr> for Shortcut
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<MouseButton, bool>>
+        + Simulate<SetTo<Char, bool>>,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        for chord in &self.chords {
+            run_chord(chord, &mut SimTuple(()), simulator, set_button);
+        }
+    }
+}
+
+impl<Smltr, E> TrySimulatableMut<Smltr, E> for Shortcut
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<MouseButton, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        for (i, chord) in self.chords.iter().enumerate() {
+            try_run_chord(chord, &mut SimTuple(()), simulator, try_set_button)
+                .map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+}
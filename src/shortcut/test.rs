@@ -0,0 +1,24 @@
+use super::*;
+
+#[test]
+fn shortcut_names_round_trip() {
+    for (names, input) in NAMES {
+        for name in *names {
+            assert_eq!(parse_input(name), Some(*input), "{name}");
+        }
+        let shortcut = Shortcut::new(vec![vec![*input]]);
+        assert_eq!(shortcut.to_string(), names[0]);
+        assert_eq!(Shortcut::parse(names[0]), Ok(shortcut));
+    }
+}
+
+#[test]
+fn shortcut_letter_keys() {
+    let shortcut = Shortcut::new(vec![vec![
+        Key::Control.into(),
+        Key::A.into(),
+        Char('a').into(),
+    ]]);
+    assert_eq!(shortcut.to_string(), "Control+KeyA+a");
+    assert_eq!(Shortcut::parse(&shortcut.to_string()), Ok(shortcut));
+}
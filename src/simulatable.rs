@@ -1,8 +1,250 @@
 //! Module for implementing a thing can be simulated
 
+use std::{error::Error, fmt};
+
 /// Simulatable is a thing that can be simulated by a simulator.
 /// In this crate, it is implemented on combinators and input event.
 pub trait Simulatable<Smlt>: Sized {
     /// Simulate this input.
     fn run_with(self, simulator: &mut Smlt);
 }
+
+/// By-reference counterpart of [`Simulatable`].
+/// Simulate without consuming self, so the same simulatable can be stored
+/// once and simulated many times without cloning.
+///
+/// It is implemented on every input event and combinator in this crate.
+/// [`repeat_mut`](crate::combinator::Combine::repeat_mut) and the loop combinators
+/// require this, [`repeat`](crate::combinator::Combine::repeat) clones instead.
+///
+/// ```
+/// use kemuler::{prelude::*, combinator::Call, event_log::EventLog};
+/// use kemuler::simulatable::SimulatableMut;
+///
+/// let mut s = EventLog::new();
+///
+/// // closures that are not `Clone` can be repeated
+/// let mut count = 0;
+/// Call::new(|_: &mut EventLog| count += 1).repeat_mut(3).run_with(&mut s);
+/// assert_eq!(count, 3);
+///
+/// let mut paste = Char('v').click().during(Key::Control.down());
+/// paste.run_mut(&mut s);
+/// paste.run_mut(&mut s);
+/// assert_eq!(s.presses(Char('v')), 2);
+/// ```
+pub trait SimulatableMut<Smlt> {
+    /// Simulate this input by reference.
+    fn run_mut(&mut self, simulator: &mut Smlt);
+}
+
+/// Fallible counterpart of [`SimulatableMut`].
+pub trait TrySimulatableMut<Smlt, E> {
+    /// Try to simulate this input by reference.
+    fn try_run_mut(&mut self, simulator: &mut Smlt) -> Result<(), StepError<E>>;
+}
+
+/// Object-safe counterpart of [`Simulatable`].
+/// It is implemented for every `Simulatable`.
+///
+/// Different simulatables can be boxed and stored together,
+/// `Box<dyn DynSimulatable<_>>` and `Vec<Box<dyn DynSimulatable<_>>>`
+/// are `Simulatable` themselves so they can be mixed with other combinators.
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, event_log::EventLog, simulatable::DynSimulatable};
+///
+/// let mut s = EventLog::new();
+/// let config = ["click", "tab", "click"];
+///
+/// let mut script: Vec<Box<dyn DynSimulatable<EventLog>>> = Vec::new();
+/// for step in config {
+///     match step {
+///         "click" => script.push(MouseButton::Left.click().boxed()),
+///         "tab" => script.push(Key::Tab.click().during(Key::Alt.down()).boxed()),
+///         _ => unreachable!(),
+///     }
+/// }
+/// script.then(Key::Enter.click()).run_with(&mut s);
+///
+/// assert_events!(
+///     s, 0,
+///     MouseButton::Left.down(),
+///     MouseButton::Left.up(),
+///     Key::Alt.down(),
+///     Key::Tab.down(),
+///     Key::Tab.up(),
+///     Key::Alt.up(),
+///     MouseButton::Left.down(),
+///     MouseButton::Left.up(),
+///     Key::Enter.down(),
+///     Key::Enter.up(),
+/// );
+/// ```
+pub trait DynSimulatable<Smlt> {
+    /// Simulate this boxed input.
+    fn run_boxed(self: Box<Self>, simulator: &mut Smlt);
+}
+
+impl<S, Smlt> DynSimulatable<Smlt> for S
+where
+    S: Simulatable<Smlt>,
+{
+    fn run_boxed(self: Box<Self>, simulator: &mut Smlt) {
+        (*self).run_with(simulator)
+    }
+}
+
+macro_rules! impl_dyn {
+    ($($bounds:tt)*) => {
+        impl<'a, Smlt> Simulatable<Smlt> for Box<dyn DynSimulatable<Smlt> $($bounds)* + 'a> {
+            fn run_with(self, simulator: &mut Smlt) {
+                self.run_boxed(simulator)
+            }
+        }
+
+        impl<'a, Smlt> Simulatable<Smlt> for Vec<Box<dyn DynSimulatable<Smlt> $($bounds)* + 'a>> {
+            fn run_with(self, simulator: &mut Smlt) {
+                for s in self {
+                    s.run_boxed(simulator);
+                }
+            }
+        }
+    };
+}
+
+impl_dyn!();
+impl_dyn!(+ Send);
+
+/// Object-safe counterpart of [`SimulatableMut`].
+/// It is implemented for every `SimulatableMut`.
+///
+/// Like [`DynSimulatable`], but the boxes can be simulated by reference,
+/// so a stored script can be run many times.
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, event_log::EventLog, simulatable::DynSimulatableMut};
+///
+/// let mut s = EventLog::new();
+/// let script: Vec<Box<dyn DynSimulatableMut<EventLog>>> = vec![
+///     MouseButton::Left.click().boxed_mut(),
+///     Key::Tab.click().boxed_mut(),
+/// ];
+/// script.repeat_mut(2).run_with(&mut s);
+///
+/// assert_eq!(s.presses(MouseButton::Left), 2);
+/// assert_eq!(s.presses(Key::Tab), 2);
+/// ```
+pub trait DynSimulatableMut<Smlt> {
+    /// Simulate this boxed input by reference.
+    fn run_boxed_mut(&mut self, simulator: &mut Smlt);
+}
+
+impl<S, Smlt> DynSimulatableMut<Smlt> for S
+where
+    S: SimulatableMut<Smlt>,
+{
+    fn run_boxed_mut(&mut self, simulator: &mut Smlt) {
+        self.run_mut(simulator)
+    }
+}
+
+macro_rules! impl_dyn_mut {
+    ($($bounds:tt)*) => {
+        impl<'a, Smlt> Simulatable<Smlt> for Box<dyn DynSimulatableMut<Smlt> $($bounds)* + 'a> {
+            fn run_with(mut self, simulator: &mut Smlt) {
+                (*self).run_boxed_mut(simulator)
+            }
+        }
+
+        impl<'a, Smlt> SimulatableMut<Smlt> for Box<dyn DynSimulatableMut<Smlt> $($bounds)* + 'a> {
+            fn run_mut(&mut self, simulator: &mut Smlt) {
+                (**self).run_boxed_mut(simulator)
+            }
+        }
+
+        impl<'a, Smlt> Simulatable<Smlt> for Vec<Box<dyn DynSimulatableMut<Smlt> $($bounds)* + 'a>> {
+            fn run_with(mut self, simulator: &mut Smlt) {
+                self.run_mut(simulator)
+            }
+        }
+
+        impl<'a, Smlt> SimulatableMut<Smlt> for Vec<Box<dyn DynSimulatableMut<Smlt> $($bounds)* + 'a>> {
+            fn run_mut(&mut self, simulator: &mut Smlt) {
+                for s in self {
+                    (**s).run_boxed_mut(simulator);
+                }
+            }
+        }
+    };
+}
+
+impl_dyn_mut!();
+impl_dyn_mut!(+ Send);
+
+/// Fallible counterpart of [`Simulatable`].
+/// The simulation stops at the first failing input event.
+///
+/// `E` is the error of the simulator, usually `TrySimulate::Error`.
+pub trait TrySimulatable<Smlt, E>: Sized {
+    /// Try to simulate this input.
+    fn try_run_with(self, simulator: &mut Smlt) -> Result<(), StepError<E>>;
+}
+
+/// Error returned from [`TrySimulatable::try_run_with`].
+///
+/// `step` is the path to the input event that failed, outermost first.
+/// Each combinator that runs things one by one adds its index to the path:
+/// tuple index for `SimTuple`, item index for `SimIter`,
+/// iteration for `Repeat` and `0`, `1`, `2` for
+/// *during*, *simulate* and *inverted during* of `During`.
+/// A plain input event that failed has an empty path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StepError<E> {
+    pub step: Vec<usize>,
+    pub error: E,
+}
+
+impl<E> StepError<E> {
+    /// Error of an input event that failed by itself.
+    pub fn new(error: E) -> StepError<E> {
+        StepError {
+            step: Vec::new(),
+            error,
+        }
+    }
+
+    /// Put `index` in front of the path.
+    /// Used by combinators to mark which of their steps failed.
+    pub fn at(mut self, index: usize) -> StepError<E> {
+        self.step.insert(0, index);
+        self
+    }
+}
+
+impl<E> fmt::Display for StepError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step [")?;
+        for (i, index) in self.step.iter().enumerate() {
+            if i != 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{index}")?;
+        }
+        write!(f, "] failed: {}", self.error)
+    }
+}
+
+impl<E> Error for StepError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -7,6 +7,36 @@ pub trait Simulate<S> {
     fn simulate(&mut self, simulatable: S);
 }
 
+/// Trait to implement for a simulator that may fail to simulate an input.
+/// This is the fallible counterpart of [`Simulate`].
+pub trait TrySimulate<S> {
+    /// What went wrong while simulating.
+    type Error;
+
+    /// Try to simulate this simulatable
+    fn try_simulate(&mut self, simulatable: S) -> Result<(), Self::Error>;
+}
+
+impl<S, Smltr> Simulate<S> for &mut Smltr
+where
+    Smltr: Simulate<S> + ?Sized,
+{
+    fn simulate(&mut self, simulatable: S) {
+        (**self).simulate(simulatable)
+    }
+}
+
+impl<S, Smltr> TrySimulate<S> for &mut Smltr
+where
+    Smltr: TrySimulate<S> + ?Sized,
+{
+    type Error = Smltr::Error;
+
+    fn try_simulate(&mut self, simulatable: S) -> Result<(), Self::Error> {
+        (**self).try_simulate(simulatable)
+    }
+}
+
 impl<S, Smltr0, Smltr1> Simulate<S> for (&mut Smltr0, &mut Smltr1)
 where
     S: Clone,
@@ -18,3 +48,17 @@ where
         self.1.simulate(simulatable);
     }
 }
+
+impl<S, Smltr0, Smltr1> TrySimulate<S> for (&mut Smltr0, &mut Smltr1)
+where
+    S: Clone,
+    Smltr0: TrySimulate<S>,
+    Smltr1: TrySimulate<S, Error = Smltr0::Error>,
+{
+    type Error = Smltr0::Error;
+
+    fn try_simulate(&mut self, simulatable: S) -> Result<(), Self::Error> {
+        self.0.try_simulate(simulatable.clone())?;
+        self.1.try_simulate(simulatable)
+    }
+}
@@ -0,0 +1,271 @@
+//! Simulator wrapper that keeps track of the input state.
+
+use std::time::Duration;
+
+use crate::{
+    cancel::{CancelError, CancelHandle, Cancelled},
+    clock::{Clock, TryClock},
+    common_inputs::{
+        ButtonLike, Char, Key, MouseButton, MousePosition, MouseScroll, PhysicalKey, RawKey,
+        StrSimTuple,
+    },
+    input_event::*,
+    simulator::{Simulate, TrySimulate},
+};
+
+#[cfg(test)]
+mod test;
+
+/// A simulator wrapper that passes every input to the inner simulator
+/// while keeping a model of which buttons are held and where the cursor is.
+///
+/// Tracked simulatables:
+/// - `SetTo   <common_inputs::Key          , bool>`
+/// - `SetTo   <common_inputs::MouseButton  , bool>`
+/// - `SetTo   <common_inputs::Char         , bool>`
+/// - `SetTo   <common_inputs::PhysicalKey  , bool>`
+/// - `SetTo   <common_inputs::RawKey       , bool>`
+/// - `SetTo   <common_inputs::MousePosition, (i32, i32)>`
+/// - `ChangeBy<common_inputs::MousePosition, (i32, i32)>`
+///
+/// Passed through without tracking:
+/// - `ChangeBy<common_inputs::MouseScroll  , (i32, i32)>`
+/// - `Execute <common_inputs::StrSimTuple>`
+///
+/// The state is only updated after the inner simulator accepted the input.
+///
+/// ```
+/// # use kemuler::event_log::EventLog as Simulator;
+/// use kemuler::{prelude::*, state_tracker::StateTracker};
+///
+/// let mut s = StateTracker::new(Simulator::new());
+///
+/// Key::Shift.down()
+///     .then(MousePosition.move_to(10, 10))
+///     .then(MousePosition.move_by(5, -5))
+///     .run_with(&mut s);
+///
+/// assert!(s.is_down(Key::Shift));
+/// assert_eq!(s.cursor(), Some((15, 5)));
+///
+/// MouseButton::Left.down()
+///     .then(Key::Shift.up())
+///     .then(Char('a').down())
+///     .run_with(&mut s);
+///
+/// assert!(!s.is_down(Key::Shift));
+/// assert_eq!(
+///     s.held_inputs(),
+///     [MouseButton::Left.into(), Char('a').into()]
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StateTracker<Smltr> {
+    inner: Smltr,
+    held: Vec<ButtonLike>,
+    cursor: Option<(i32, i32)>,
+}
+
+impl<Smltr> StateTracker<Smltr> {
+    /// Track inputs to `inner` with an unknown cursor position.
+    pub fn new(inner: Smltr) -> StateTracker<Smltr> {
+        StateTracker {
+            inner,
+            held: Vec::new(),
+            cursor: None,
+        }
+    }
+
+    /// Track inputs to `inner` with a known cursor position.
+    pub fn with_cursor(inner: Smltr, cursor: (i32, i32)) -> StateTracker<Smltr> {
+        StateTracker {
+            cursor: Some(cursor),
+            ..StateTracker::new(inner)
+        }
+    }
+
+    pub fn inner(&self) -> &Smltr {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Smltr {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> Smltr {
+        self.inner
+    }
+
+    /// Is this input currently held down?
+    pub fn is_down<I: Into<ButtonLike>>(&self, input: I) -> bool {
+        self.held.contains(&input.into())
+    }
+
+    /// Cursor position, if it is known.
+    /// It becomes known once the mouse is moved to an absolute position
+    /// or if the tracker was created with [`StateTracker::with_cursor`].
+    /// Relative movements before that are ignored.
+    pub fn cursor(&self) -> Option<(i32, i32)> {
+        self.cursor
+    }
+
+    /// Every held input, in the order they were pressed.
+    pub fn held_inputs(&self) -> &[ButtonLike] {
+        &self.held
+    }
+
+    fn track_button(&mut self, input: ButtonLike, to: bool) {
+        track_held(&mut self.held, input, to)
+    }
+
+    fn track_move_to(&mut self, to: (i32, i32)) {
+        self.cursor = Some(to);
+    }
+
+    fn track_move_by(&mut self, (x, y): (i32, i32)) {
+        if let Some((cx, cy)) = self.cursor {
+            self.cursor = Some((cx.saturating_add(x), cy.saturating_add(y)));
+        }
+    }
+}
+
+/// Update a list of held inputs in press order.
+/// Pressing a held input or releasing a released input does nothing.
+pub(crate) fn track_held(held: &mut Vec<ButtonLike>, input: ButtonLike, to: bool) {
+    let position = held.iter().position(|h| *h == input);
+    match (to, position) {
+        (true, None) => held.push(input),
+        (false, Some(i)) => {
+            held.remove(i);
+        }
+        _ => {}
+    }
+}
+
+macro_rules! impl_tracker {
+    ($( $(#[$attr:meta])* $ty:ty => |$this:ident, $event:ident| $track:block )*) => {
+        $(
+            $(#[$attr])*
+            impl<Smltr> Simulate<$ty> for StateTracker<Smltr>
+            where
+                Smltr: Simulate<$ty>,
+            {
+                fn simulate(&mut self, simulatable: $ty) {
+                    self.inner.simulate(simulatable);
+                    let ($this, $event) = (self, simulatable);
+                    $track
+                }
+            }
+
+            $(#[$attr])*
+            impl<Smltr> TrySimulate<$ty> for StateTracker<Smltr>
+            where
+                Smltr: TrySimulate<$ty>,
+            {
+                type Error = Smltr::Error;
+
+                fn try_simulate(&mut self, simulatable: $ty) -> Result<(), Self::Error> {
+                    self.inner.try_simulate(simulatable)?;
+                    let ($this, $event) = (self, simulatable);
+                    $track
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_tracker! {
+    SetTo<Key, bool> => |this, e| { this.track_button(e.input.into(), e.to) }
+    SetTo<MouseButton, bool> => |this, e| { this.track_button(e.input.into(), e.to) }
+    SetTo<Char, bool> => |this, e| { this.track_button(e.input.into(), e.to) }
+    SetTo<PhysicalKey, bool> => |this, e| { this.track_button(e.input.into(), e.to) }
+    SetTo<RawKey, bool> => |this, e| { this.track_button(e.input.into(), e.to) }
+    SetTo<MousePosition, (i32, i32)> => |this, e| { this.track_move_to(e.to) }
+    ChangeBy<MousePosition, (i32, i32)> => |this, e| { this.track_move_by(e.by) }
+    ChangeBy<MouseScroll, (i32, i32)> => |_this, _e| {}
+}
+
+impl<'a, Smltr> Simulate<Execute<StrSimTuple<'a>>> for StateTracker<Smltr>
+where
+    Smltr: Simulate<Execute<StrSimTuple<'a>>>,
+{
+    fn simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) {
+        self.inner.simulate(simulatable)
+    }
+}
+
+impl<'a, Smltr> TrySimulate<Execute<StrSimTuple<'a>>> for StateTracker<Smltr>
+where
+    Smltr: TrySimulate<Execute<StrSimTuple<'a>>>,
+{
+    type Error = Smltr::Error;
+
+    fn try_simulate(&mut self, simulatable: Execute<StrSimTuple<'a>>) -> Result<(), Self::Error> {
+        self.inner.try_simulate(simulatable)
+    }
+}
+
+impl<Smltr> Clock for StateTracker<Smltr>
+where
+    Smltr: Clock,
+{
+    fn now(&self) -> Duration {
+        self.inner.now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.inner.sleep(duration)
+    }
+
+    fn spin_sleep(&mut self, duration: Duration) {
+        self.inner.spin_sleep(duration)
+    }
+
+    fn sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        self.inner.sleep_or_cancel(duration, handle)
+    }
+
+    fn spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), Cancelled> {
+        self.inner.spin_sleep_or_cancel(duration, handle)
+    }
+}
+
+impl<Smltr> TryClock for StateTracker<Smltr>
+where
+    Smltr: TryClock,
+{
+    type Error = Smltr::Error;
+
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        self.inner.try_sleep(duration)
+    }
+
+    fn try_spin_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        self.inner.try_spin_sleep(duration)
+    }
+
+    fn try_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        self.inner.try_sleep_or_cancel(duration, handle)
+    }
+
+    fn try_spin_sleep_or_cancel(
+        &mut self,
+        duration: Duration,
+        handle: &CancelHandle,
+    ) -> Result<(), CancelError<Self::Error>> {
+        self.inner.try_spin_sleep_or_cancel(duration, handle)
+    }
+}
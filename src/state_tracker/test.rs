@@ -0,0 +1,75 @@
+use crate::event_log::EventLog as S;
+use crate::prelude::*;
+use crate::simulator::TrySimulate;
+
+use super::*;
+
+/// Rejects every input.
+struct Rejecting;
+
+impl<T> TrySimulate<T> for Rejecting {
+    type Error = ();
+
+    fn try_simulate(&mut self, _simulatable: T) -> Result<(), ()> {
+        Err(())
+    }
+}
+
+#[test]
+fn state_tracker_move_by_saturates() {
+    let mut s = StateTracker::with_cursor(S::new(), (i32::MAX - 1, i32::MIN + 1));
+    MousePosition.move_by(10, -10).run_with(&mut s);
+    assert_eq!(s.cursor(), Some((i32::MAX, i32::MIN)));
+
+    MousePosition.move_by(i32::MIN, i32::MAX).run_with(&mut s);
+    assert_eq!(s.cursor(), Some((-1, -1)));
+}
+
+#[test]
+fn state_tracker_repeated_press() {
+    let mut s = StateTracker::new(S::new());
+    Key::Shift
+        .down()
+        .then(Key::Shift.down())
+        .then(Char('a').down())
+        .run_with(&mut s);
+    assert_eq!(s.held_inputs(), [Key::Shift.into(), Char('a').into()]);
+
+    Key::Shift
+        .up()
+        .then(Key::Shift.up())
+        .then(Char('b').up())
+        .run_with(&mut s);
+    assert_eq!(s.held_inputs(), [Char('a').into()]);
+}
+
+#[test]
+fn state_tracker_rejected_input_is_not_tracked() {
+    let mut s = StateTracker::new(Rejecting);
+    assert!(Key::Shift.down().try_run_with(&mut s).is_err());
+    assert!(MousePosition.move_to(1, 1).try_run_with(&mut s).is_err());
+    assert!(!s.is_down(Key::Shift));
+    assert_eq!(s.cursor(), None);
+}
+
+#[test]
+fn state_tracker_physical_key() {
+    let mut s = StateTracker::new(S::new());
+    PhysicalKey::ShiftLeft
+        .down()
+        .then(PhysicalKey::KeyA.down())
+        .then(PhysicalKey::KeyA.up())
+        .run_with(&mut s);
+    assert!(s.is_down(PhysicalKey::ShiftLeft));
+    assert!(!s.is_down(PhysicalKey::KeyA));
+    assert_eq!(s.held_inputs(), [PhysicalKey::ShiftLeft.into()]);
+}
+
+#[test]
+fn state_tracker_raw_key() {
+    let mut s = StateTracker::new(S::new());
+    RawKey::linux_evdev(42).down().run_with(&mut s);
+    assert!(s.is_down(RawKey::linux_evdev(42)));
+    // the same code in another space is another key
+    assert!(!s.is_down(RawKey::usb_hid(42)));
+}
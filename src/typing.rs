@@ -0,0 +1,668 @@
+//! Type text with key presses, for simulators that can't type text directly.
+
+use std::{fmt, time::Duration};
+
+use crate::{
+    clock::{Clock, TryClock},
+    combinator::{Call, Combine, ReleaseGuard, Sleep},
+    common_inputs::{Char, Key},
+    input_event::SetTo,
+    layout::{self, Layout, Modifiers, PhysicalKey},
+    simulatable::{Simulatable, SimulatableMut, StepError, TrySimulatable, TrySimulatableMut},
+    simulator::{Simulate, TrySimulate},
+};
+
+#[cfg(test)]
+mod test;
+
+/// A key to click to type a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Stroke {
+    Key(Key),
+    Char(Char),
+    /// A dead key, it types no character to click as a `Char`.
+    Physical(PhysicalKey),
+}
+
+/// Click a stroke during the held keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Press {
+    stroke: Stroke,
+    held: Vec<Key>,
+}
+
+/// Keys to hold for the modifiers, AltGr is held as [`Key::RightAlt`].
+fn modifier_keys(modifiers: Modifiers) -> Vec<Key> {
+    let mut keys = Vec::new();
+    if modifiers.alt_gr {
+        keys.push(Key::RightAlt);
+    }
+    if modifiers.shift {
+        keys.push(Key::Shift);
+    }
+    keys
+}
+
+/// The press of a physical key, clicked as the `Char` it types without modifiers,
+/// or as the physical key for a dead key that types nothing.
+fn press_key(layout: &Layout, key: PhysicalKey, modifiers: Modifiers) -> Option<Press> {
+    let stroke = match key {
+        PhysicalKey::Space => Stroke::Key(Key::Space),
+        PhysicalKey::Enter => Stroke::Key(Key::Enter),
+        PhysicalKey::Tab => Stroke::Key(Key::Tab),
+        key => match layout.char_for(key, Modifiers::NONE) {
+            Some(c) => Stroke::Char(Char(c)),
+            None => {
+                layout.accent_for(key, Modifiers::NONE)?;
+                Stroke::Physical(key)
+            }
+        },
+    };
+    Some(Press {
+        stroke,
+        held: modifier_keys(modifiers),
+    })
+}
+
+/// Presses that type every character of `s` with a single keystroke.
+fn press_str(layout: &Layout, s: &str) -> Option<Vec<Press>> {
+    s.chars()
+        .map(|c| {
+            let (key, modifiers) = layout.keystroke_for(c)?;
+            press_key(layout, key, modifiers)
+        })
+        .collect()
+}
+
+/// How to type characters that can't be typed with the layout or compose key.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeInput {
+    /// Click the character as a [`Char`] and let the simulator deal with it.
+    Char,
+    /// Click `u` during Control and Shift, type the hex code point, then click Space.
+    /// This is how GTK and IBus take Unicode input on Linux.
+    CtrlShiftU,
+}
+
+impl Default for UnicodeInput {
+    fn default() -> Self {
+        UnicodeInput::Char
+    }
+}
+
+/// How fast [`TypeText`] types, to pace typing like a person
+/// for applications that drop keys typed too fast.
+///
+/// Every delay varies randomly by up to `variance` percent.
+/// The variance comes from `seed` so the same seed types with the same delays.
+///
+/// ```
+/// use kemuler::{typing::TypingSpeed, utils::time::millis};
+///
+/// let speed = TypingSpeed::new(60).variance(10).seed(42).hold(millis(30));
+/// // a word is 5 characters
+/// assert_eq!(speed.wpm(), 60);
+/// assert_eq!(speed.interval(), millis(200));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypingSpeed {
+    /// Words per minute, never zero.
+    wpm: u32,
+    /// Maximum variance of every delay in percent, up to 100.
+    pub variance: u32,
+    pub seed: u64,
+    /// Extra delay after punctuation and whitespace.
+    pub pause: Duration,
+    /// Minimum time a key is held down for.
+    pub hold: Duration,
+}
+
+impl TypingSpeed {
+    /// Type at `wpm` words per minute varying by 25 percent,
+    /// pausing for a character after punctuation and whitespace
+    /// and holding keys for at least 20 milliseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `wpm` is zero.
+    pub fn new(wpm: u32) -> TypingSpeed {
+        assert!(wpm != 0, "typing speed must not be zero");
+        TypingSpeed {
+            wpm,
+            variance: 25,
+            seed: 0,
+            pause: Duration::from_secs(12) / wpm,
+            hold: Duration::from_millis(20),
+        }
+    }
+
+    /// Words per minute.
+    pub fn wpm(&self) -> u32 {
+        self.wpm
+    }
+
+    pub fn variance(mut self, percent: u32) -> TypingSpeed {
+        self.variance = percent;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> TypingSpeed {
+        self.seed = seed;
+        self
+    }
+
+    pub fn pause(mut self, pause: Duration) -> TypingSpeed {
+        self.pause = pause;
+        self
+    }
+
+    pub fn hold(mut self, hold: Duration) -> TypingSpeed {
+        self.hold = hold;
+        self
+    }
+
+    /// The delay between characters before variance.
+    pub fn interval(&self) -> Duration {
+        // 60 seconds / (wpm * 5 characters)
+        Duration::from_secs(12) / self.wpm
+    }
+}
+
+/// Typos made while typing that are corrected right away,
+/// for tests that type like a person.
+///
+/// A typo clicks a key next to the key of the character, pauses,
+/// then clicks Backspace and types the character,
+/// so the typed text is the same with or without typos.
+/// Only characters typed with a single key get typos,
+/// and the key next to it must type a character with the same modifiers.
+///
+/// Typos come from `seed` so the same seed makes the same typos.
+///
+/// ```
+/// use kemuler::{prelude::*, event_log::EventLog, typing::Typos};
+///
+/// let mut s = EventLog::new();
+/// StrSimTuple("hi").type_text().typos(Typos::new(100)).run_with(&mut s);
+/// assert_eq!(s.presses(Key::Backspace), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Typos {
+    /// Chance of a typo for every character in percent.
+    pub chance: u32,
+    pub seed: u64,
+    /// Pause before correcting a typo.
+    pub pause: Duration,
+}
+
+impl Typos {
+    /// Make typos by `chance` percent, pausing for 300 milliseconds before correcting one.
+    pub fn new(chance: u32) -> Typos {
+        Typos {
+            chance,
+            seed: 0,
+            pause: Duration::from_millis(300),
+        }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Typos {
+        self.seed = seed;
+        self
+    }
+
+    pub fn pause(mut self, pause: Duration) -> Typos {
+        self.pause = pause;
+        self
+    }
+}
+
+/// SplitMix64, random enough for delays and without a dependency.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0.0..1.0`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The typos while typing.
+#[derive(Debug, Clone)]
+struct Mistakes {
+    typos: Option<Typos>,
+    rng: Rng,
+}
+
+impl Mistakes {
+    fn new(typos: Option<Typos>) -> Mistakes {
+        Mistakes {
+            typos,
+            rng: Rng(typos.map_or(0, |typos| typos.seed)),
+        }
+    }
+
+    /// The press of a typo for the character and the pause after it, if one is made.
+    fn typo_for(&mut self, layout: &Layout, c: char) -> Option<(Press, Sleep)> {
+        let typos = self.typos?;
+        if self.rng.next_f64() * 100.0 >= f64::from(typos.chance) {
+            return None;
+        }
+        let (key, modifiers) = layout.keystroke_for(c)?;
+        let neighbors: Vec<PhysicalKey> = key
+            .neighbors()
+            .into_iter()
+            .filter(|key| layout.char_for(*key, modifiers).is_some())
+            .collect();
+        if neighbors.is_empty() {
+            return None;
+        }
+        let typo = neighbors[(self.rng.next_u64() % neighbors.len() as u64) as usize];
+        Some((press_key(layout, typo, modifiers)?, Sleep(typos.pause)))
+    }
+}
+
+/// The sleeps of a typing speed while typing.
+#[derive(Debug, Clone)]
+struct Pace {
+    speed: Option<TypingSpeed>,
+    rng: Rng,
+    previous: Option<char>,
+}
+
+impl Pace {
+    fn new(speed: Option<TypingSpeed>) -> Pace {
+        Pace {
+            speed,
+            rng: Rng(speed.map_or(0, |speed| speed.seed)),
+            previous: None,
+        }
+    }
+
+    /// The delay varied by up to the variance either way.
+    fn vary(&mut self, speed: TypingSpeed, delay: Duration) -> Duration {
+        let variance = f64::from(speed.variance.min(100)) / 100.0;
+        delay.mul_f64(1.0 + variance * (self.rng.next_f64() * 2.0 - 1.0))
+    }
+
+    /// The sleep before typing the character, none before the first one.
+    fn before(&mut self, c: char) -> Option<Sleep> {
+        let previous = self.previous.replace(c)?;
+        let speed = self.speed?;
+        let mut delay = self.vary(speed, speed.interval());
+        if previous.is_whitespace() || previous.is_ascii_punctuation() {
+            delay += self.vary(speed, speed.pause);
+        }
+        Some(Sleep(delay))
+    }
+
+    /// The sleep between pressing and releasing a key, never below the hold.
+    fn hold(&mut self) -> Option<Sleep> {
+        let speed = self.speed?;
+        let variance = f64::from(speed.variance.min(100)) / 100.0;
+        Some(Sleep(
+            speed.hold + speed.hold.mul_f64(variance * self.rng.next_f64()),
+        ))
+    }
+}
+
+/// Type text by clicking a key for every character.
+///
+/// Characters are typed with the key that types them on the [`Layout`],
+/// US by default, clicked as the [`Char`] it types without modifiers.
+/// Uppercase letters and symbols are typed during Shift or AltGr,
+/// so on US `'A'` is `Char('a')` and `'!'` is `Char('1')` during Shift.
+/// AltGr is held as [`Key::RightAlt`].
+/// `'\n'`, `'\t'` and `' '` click [`Key::Enter`], [`Key::Tab`] and [`Key::Space`].
+///
+/// Accented letters not on the layout are typed with a dead key of the layout,
+/// like `´` then `e` for `é` on German layouts,
+/// or with a compose sequence like Compose, `'`, `e` if a compose key is set.
+/// Otherwise they are typed with the [`UnicodeInput`], clicked as they are by default.
+/// Dead keys type no character, so they are clicked as their [`PhysicalKey`],
+/// like [`PhysicalKey::Equal`] for `´` on German layouts.
+///
+/// Characters are typed at once unless a [`TypingSpeed`] is set,
+/// which sleeps with the simulator's [`Clock`] between characters and while keys are held.
+/// [`Typos`] can be made and corrected along the way.
+///
+/// When fallibly simulated, a failure is reported at the index of the character.
+///
+/// ```
+/// # use kemuler::assert_events;
+/// use kemuler::{prelude::*, event_log::EventLog};
+///
+/// let mut s = EventLog::new();
+/// StrSimTuple("Hi!\n").type_text().run_with(&mut s);
+/// assert_events!(
+///     s, 0,
+///     Key::Shift.down(),
+///     Char('h').down(),
+///     Char('h').up(),
+///     Key::Shift.up(),
+///     Char('i').down(),
+///     Char('i').up(),
+///     Key::Shift.down(),
+///     Char('1').down(),
+///     Char('1').up(),
+///     Key::Shift.up(),
+///     Key::Enter.down(),
+///     Key::Enter.up(),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeText {
+    pub text: String,
+    pub layout: Layout,
+    pub compose_key: Option<Key>,
+    pub unicode_input: UnicodeInput,
+    pub speed: Option<TypingSpeed>,
+    pub typos: Option<Typos>,
+}
+
+impl TypeText {
+    pub fn new(text: impl Into<String>) -> TypeText {
+        TypeText {
+            text: text.into(),
+            layout: layout::US,
+            compose_key: None,
+            unicode_input: UnicodeInput::default(),
+            speed: None,
+            typos: None,
+        }
+    }
+
+    /// Type with the keys of this layout.
+    pub fn layout(mut self, layout: Layout) -> TypeText {
+        self.layout = layout;
+        self
+    }
+
+    /// Type accented letters with compose sequences starting with this key
+    /// when the layout has no dead key for them.
+    pub fn compose_key(mut self, key: Key) -> TypeText {
+        self.compose_key = Some(key);
+        self
+    }
+
+    /// Type characters that can't be typed otherwise with this.
+    pub fn unicode_input(mut self, unicode_input: UnicodeInput) -> TypeText {
+        self.unicode_input = unicode_input;
+        self
+    }
+
+    /// Type at this speed instead of at once.
+    pub fn speed(mut self, speed: TypingSpeed) -> TypeText {
+        self.speed = Some(speed);
+        self
+    }
+
+    /// Make and correct typos.
+    pub fn typos(mut self, typos: Typos) -> TypeText {
+        self.typos = Some(typos);
+        self
+    }
+
+    /// The presses that type the character.
+    fn presses_for(&self, c: char) -> Vec<Press> {
+        let layout = &self.layout;
+        let keystrokes = layout.keystrokes_for(c).and_then(|keystrokes| {
+            keystrokes
+                .into_iter()
+                .map(|(key, modifiers)| press_key(layout, key, modifiers))
+                .collect()
+        });
+        let compose = || {
+            let compose_key = self.compose_key?;
+            let (accent, letter) = layout::decompose(c)?;
+            let accent = match accent {
+                '´' => '\'',
+                '¨' => '"',
+                '¸' => ',',
+                accent => accent,
+            };
+            let mut presses = vec![Press {
+                stroke: Stroke::Key(compose_key),
+                held: Vec::new(),
+            }];
+            presses.extend(press_str(layout, &format!("{accent}{letter}"))?);
+            Some(presses)
+        };
+        let unicode = || match self.unicode_input {
+            UnicodeInput::Char => None,
+            UnicodeInput::CtrlShiftU => {
+                let mut presses = press_str(layout, "u")?;
+                presses[0].held.splice(0..0, [Key::Control, Key::Shift]);
+                presses.extend(press_str(layout, &format!("{:x} ", c as u32))?);
+                Some(presses)
+            }
+        };
+        keystrokes
+            .or_else(compose)
+            .or_else(unicode)
+            .unwrap_or_else(|| {
+                vec![Press {
+                    stroke: Stroke::Char(Char(c)),
+                    held: Vec::new(),
+                }]
+            })
+    }
+}
+
+fn click<Smltr>(stroke: Stroke, hold: Option<Sleep>, simulator: &mut Smltr)
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<Char, bool>>
+        + Simulate<SetTo<PhysicalKey, bool>>
+        + Clock,
+{
+    match stroke {
+        Stroke::Key(key) => key.down().then(hold).then(key.up()).run_with(simulator),
+        Stroke::Char(c) => c.down().then(hold).then(c.up()).run_with(simulator),
+        Stroke::Physical(key) => key.down().then(hold).then(key.up()).run_with(simulator),
+    }
+}
+
+fn try_click<Smltr, E>(
+    stroke: Stroke,
+    hold: Option<Sleep>,
+    simulator: &mut Smltr,
+) -> Result<(), StepError<E>>
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>
+        + TrySimulate<SetTo<PhysicalKey, bool>, Error = E>
+        + TryClock<Error = E>,
+{
+    match stroke {
+        Stroke::Key(key) => key.down().then(hold).then(key.up()).try_run_with(simulator),
+        Stroke::Char(c) => c.down().then(hold).then(c.up()).try_run_with(simulator),
+        Stroke::Physical(key) => key.down().then(hold).then(key.up()).try_run_with(simulator),
+    }
+}
+
+/// Click the stroke during every held key, sleeping for `hold_for` while it's down.
+fn hold<Smltr>(held: &[Key], stroke: Stroke, hold_for: Option<Sleep>, simulator: &mut Smltr)
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<Char, bool>>
+        + Simulate<SetTo<PhysicalKey, bool>>
+        + Clock,
+{
+    match held.split_first() {
+        Some((key, rest)) => Call::new(|s: &mut Smltr| hold(rest, stroke, hold_for, s))
+            .during(key.down())
+            .run_with(simulator),
+        None => click(stroke, hold_for, simulator),
+    }
+}
+
+/// Release the held keys, last pressed first.
+/// Errors are dropped in favor of the one that caused the release.
+fn release_held<Smltr>(held: Vec<Key>, simulator: &mut Smltr)
+where
+    Smltr: TrySimulate<SetTo<Key, bool>>,
+{
+    for key in held.into_iter().rev() {
+        let _ = simulator.try_simulate(key.up());
+    }
+}
+
+/// Fallible version of [`hold`].
+/// The steps are the presses of the held keys, the click of the stroke,
+/// then the releases of the held keys.
+fn try_hold<Smltr, E>(
+    held: &[Key],
+    stroke: Stroke,
+    hold_for: Option<Sleep>,
+    simulator: &mut Smltr,
+) -> Result<(), StepError<E>>
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>
+        + TrySimulate<SetTo<PhysicalKey, bool>, Error = E>
+        + TryClock<Error = E>,
+{
+    let mut guard = ReleaseGuard::new(simulator, Vec::new(), release_held);
+    for (i, key) in held.iter().enumerate() {
+        guard
+            .simulator()
+            .try_simulate(key.down())
+            .map_err(|e| StepError::new(e).at(i))?;
+        guard.release_mut().push(*key);
+    }
+    let mut step = held.len();
+    try_click(stroke, hold_for, guard.simulator()).map_err(|e| e.at(step))?;
+    while let Some(key) = guard.release_mut().pop() {
+        step += 1;
+        guard
+            .simulator()
+            .try_simulate(key.up())
+            .map_err(|e| StepError::new(e).at(step))?;
+    }
+    Ok(())
+}
+
+fn type_char<Smltr>(
+    text: &TypeText,
+    pace: &mut Pace,
+    mistakes: &mut Mistakes,
+    c: char,
+    simulator: &mut Smltr,
+) where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<Char, bool>>
+        + Simulate<SetTo<PhysicalKey, bool>>
+        + Clock,
+{
+    pace.before(c).run_with(simulator);
+    if let Some((typo, pause)) = mistakes.typo_for(&text.layout, c) {
+        hold(&typo.held, typo.stroke, pace.hold(), simulator);
+        pause.run_with(simulator);
+        click(Stroke::Key(Key::Backspace), pace.hold(), simulator);
+    }
+    for press in text.presses_for(c) {
+        hold(&press.held, press.stroke, pace.hold(), simulator);
+    }
+}
+
+fn try_type_char<Smltr, E>(
+    text: &TypeText,
+    pace: &mut Pace,
+    mistakes: &mut Mistakes,
+    c: char,
+    simulator: &mut Smltr,
+) -> Result<(), StepError<E>>
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>
+        + TrySimulate<SetTo<PhysicalKey, bool>, Error = E>
+        + TryClock<Error = E>,
+{
+    pace.before(c).try_run_with(simulator)?;
+    if let Some((typo, pause)) = mistakes.typo_for(&text.layout, c) {
+        try_hold(&typo.held, typo.stroke, pace.hold(), simulator)?;
+        pause.try_run_with(simulator)?;
+        try_click(Stroke::Key(Key::Backspace), pace.hold(), simulator)?;
+    }
+    for press in text.presses_for(c) {
+        try_hold(&press.held, press.stroke, pace.hold(), simulator)?;
+    }
+    Ok(())
+}
+
+impl<Smltr> Simulatable<Smltr> for TypeText
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<Char, bool>>
+        + Simulate<SetTo<PhysicalKey, bool>>
+        + Clock,
+{
+    fn run_with(mut self, simulator: &mut Smltr) {
+        self.run_mut(simulator)
+    }
+}
+
+impl<Smltr, E> TrySimulatable<Smltr, E> for TypeText
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>
+        + TrySimulate<SetTo<PhysicalKey, bool>, Error = E>
+        + TryClock<Error = E>,
+{
+    fn try_run_with(mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        self.try_run_mut(simulator)
+    }
+}
+
+impl<Smltr> SimulatableMut<Smltr> for TypeText
+where
+    Smltr: Simulate<SetTo<Key, bool>>
+        + Simulate<SetTo<Char, bool>>
+        + Simulate<SetTo<PhysicalKey, bool>>
+        + Clock,
+{
+    fn run_mut(&mut self, simulator: &mut Smltr) {
+        let mut pace = Pace::new(self.speed);
+        let mut mistakes = Mistakes::new(self.typos);
+        for c in self.text.chars() {
+            type_char(self, &mut pace, &mut mistakes, c, simulator);
+        }
+    }
+}
+
+impl<Smltr, E> TrySimulatableMut<Smltr, E> for TypeText
+where
+    Smltr: TrySimulate<SetTo<Key, bool>, Error = E>
+        + TrySimulate<SetTo<Char, bool>, Error = E>
+        + TrySimulate<SetTo<PhysicalKey, bool>, Error = E>
+        + TryClock<Error = E>,
+{
+    fn try_run_mut(&mut self, simulator: &mut Smltr) -> Result<(), StepError<E>> {
+        let mut pace = Pace::new(self.speed);
+        let mut mistakes = Mistakes::new(self.typos);
+        for (i, c) in self.text.chars().enumerate() {
+            try_type_char(self, &mut pace, &mut mistakes, c, simulator).map_err(|e| e.at(i))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[type {:?} on {}", self.text, self.layout)?;
+        if let Some(speed) = self.speed {
+            write!(f, " at {} wpm", speed.wpm)?;
+        }
+        write!(f, "]")
+    }
+}
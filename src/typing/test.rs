@@ -0,0 +1,158 @@
+use crate::clock::VirtualClock;
+use crate::event_log::{Event, EventLog as S};
+use crate::utils::time::millis;
+
+use super::*;
+
+#[test]
+fn typing_speed() {
+    let typed = |speed: TypingSpeed| {
+        let mut s = S::with_clock(VirtualClock::new());
+        TypeText::new("a, b").speed(speed).run_with(&mut s);
+        s
+    };
+    let speed = TypingSpeed::new(60).variance(20).seed(7);
+    let s = typed(speed);
+
+    let mut at_once = S::new();
+    TypeText::new("a, b").run_with(&mut at_once);
+    assert_eq!(s.events, at_once.events);
+
+    for i in (0..s.events.len()).step_by(2) {
+        assert_gap!(s, i, i + 1, millis(22), millis(2));
+    }
+    assert_gap!(s, 1, 2, millis(200), millis(40));
+    // after punctuation and whitespace
+    assert_gap!(s, 3, 4, millis(400), millis(80));
+    assert_gap!(s, 5, 6, millis(400), millis(80));
+
+    assert_eq!(typed(speed).times, s.times);
+    assert_ne!(typed(speed.seed(8)).times, s.times);
+}
+
+/// The text typed by the events, with Shift and Backspace.
+fn typed_text(s: &S) -> String {
+    let mut text = String::new();
+    let mut shift = false;
+    for event in &s.events {
+        match event {
+            Event::Key(SetTo {
+                input: Key::Shift,
+                to,
+            }) => shift = *to,
+            Event::Key(SetTo {
+                input: Key::Backspace,
+                to: true,
+            }) => {
+                text.pop();
+            }
+            Event::Key(SetTo {
+                input: Key::Space,
+                to: true,
+            }) => text.push(' '),
+            Event::Char(SetTo { input, to: true }) => {
+                let level = if shift {
+                    Modifiers::SHIFT
+                } else {
+                    Modifiers::NONE
+                };
+                let (key, _) = layout::US.keystroke_for(input.0).unwrap();
+                text.extend(layout::US.char_for(key, level));
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+#[test]
+fn typos_are_corrected() {
+    let text = "The quick brown fox, jumps over the lazy dog!";
+    for seed in 0..20 {
+        let mut s = S::new();
+        TypeText::new(text)
+            .typos(Typos::new(30).seed(seed).pause(Duration::ZERO))
+            .run_with(&mut s);
+        assert_eq!(typed_text(&s), text, "seed {seed}");
+    }
+
+    let mut s = S::new();
+    TypeText::new("as")
+        .typos(Typos::new(100).pause(Duration::ZERO))
+        .run_with(&mut s);
+    assert_eq!(s.presses(Key::Backspace), 2);
+    let typo = match s.events[0] {
+        Event::Char(SetTo { input, .. }) => input.0,
+        _ => panic!("expected a typo"),
+    };
+    let (key, _) = layout::US.keystroke_for(typo).unwrap();
+    assert!(PhysicalKey::KeyA.neighbors().contains(&key));
+}
+
+/// Fails to press any `Char`.
+struct NoChars(S);
+
+impl TrySimulate<SetTo<Char, bool>> for NoChars {
+    type Error = &'static str;
+
+    fn try_simulate(&mut self, simulatable: SetTo<Char, bool>) -> Result<(), Self::Error> {
+        if simulatable.to {
+            return Err("no chars");
+        }
+        self.0.simulate(simulatable);
+        Ok(())
+    }
+}
+
+impl TrySimulate<SetTo<Key, bool>> for NoChars {
+    type Error = &'static str;
+
+    fn try_simulate(&mut self, simulatable: SetTo<Key, bool>) -> Result<(), Self::Error> {
+        self.0.simulate(simulatable);
+        Ok(())
+    }
+}
+
+impl TrySimulate<SetTo<PhysicalKey, bool>> for NoChars {
+    type Error = &'static str;
+
+    fn try_simulate(&mut self, simulatable: SetTo<PhysicalKey, bool>) -> Result<(), Self::Error> {
+        self.0.simulate(simulatable);
+        Ok(())
+    }
+}
+
+impl Clock for NoChars {
+    fn now(&self) -> Duration {
+        self.0.now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        Clock::sleep(&mut self.0, duration)
+    }
+}
+
+impl TryClock for NoChars {
+    type Error = &'static str;
+
+    fn try_sleep(&mut self, duration: Duration) -> Result<(), Self::Error> {
+        Clock::sleep(&mut self.0, duration);
+        Ok(())
+    }
+}
+
+#[test]
+fn typing_failure_step() {
+    let mut s = NoChars(S::new());
+    let result = TypeText::new("\nI").try_run_with(&mut s);
+    assert_eq!(result.unwrap_err().step, [1, 1, 0, 0]);
+    let log = s.0;
+    assert_events!(
+        log,
+        0,
+        Key::Enter.down(),
+        Key::Enter.up(),
+        Key::Shift.down(),
+        Key::Shift.up(),
+    );
+}